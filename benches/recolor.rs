@@ -0,0 +1,78 @@
+//! Recolors synthetic comics the size of a regular comic and of a large infographic
+
+use criterion::{BenchmarkId, Criterion, Throughput, criterion_group, criterion_main};
+use image::{DynamicImage, GenericImageView, ImageBuffer, Pixel, Rgb, RgbImage};
+
+// Benches are built with cfg(test) but without the test harness, so the tests of the module are unused
+#[allow(unused_imports)]
+#[path = "../src/app/ui/recolor.rs"]
+mod recolor;
+use recolor::{Color, ColorMode, Levels, Recolor};
+
+const FOREGROUND: Color = [220, 220, 220];
+const BACKGROUND: Color = [30, 30, 46];
+const SIZES: [(u32, u32); 2] = [(740, 900), (2000, 6000)];
+
+/// Mostly white with black lines, gray anti-aliasing and a few colored areas, like most comics
+fn sample_comic(width: u32, height: u32) -> DynamicImage {
+    RgbImage::from_fn(width, height, |x, y| match (x % 40, y % 40) {
+        (0..=2, _) | (_, 0..=2) => Rgb([0, 0, 0]),
+        (3, _) | (_, 3) => Rgb([128, 128, 128]),
+        (20..=30, 20..=30) if (x / 40 + y / 40) % 7 == 0 => Rgb([200, 40, 40]),
+        _ => Rgb([255, 255, 255]),
+    })
+    .into()
+}
+
+/// The per-pixel implementation the lookup table replaced, kept as a baseline
+fn naive_recolor(image: &DynamicImage, foreground: Color, background: Color) -> DynamicImage {
+    let (width, height) = image.dimensions();
+    let mut out = ImageBuffer::new(width, height);
+    image.pixels().for_each(|(x, y, pixel)| {
+        let color = pixel.to_rgb().0;
+        let ratio = Rgb::from(color).to_luma().0[0] as f64 / 255.0;
+        let blended: Color = std::array::from_fn(|i| {
+            (background[i] as f64 * ratio + foreground[i] as f64 * (1.0 - ratio)) as u8
+        });
+        out.put_pixel(x, y, Rgb(blended))
+    });
+    out.into()
+}
+
+fn recolor(c: &mut Criterion) {
+    let mut group = c.benchmark_group("recolor");
+    let recolor = Recolor::new(
+        FOREGROUND,
+        BACKGROUND,
+        ColorMode::Grayscale,
+        Levels::default(),
+        true,
+    );
+    let theme_recolor = Recolor::new(
+        FOREGROUND,
+        BACKGROUND,
+        ColorMode::Theme,
+        Levels::default(),
+        true,
+    );
+    for (width, height) in SIZES {
+        let image = sample_comic(width, height);
+        let size = format!("{width}x{height}");
+        group.throughput(Throughput::Elements(width as u64 * height as u64));
+        group.bench_with_input(BenchmarkId::new("naive", &size), &image, |b, image| {
+            b.iter(|| naive_recolor(image, FOREGROUND, BACKGROUND))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("lookup_table", &size),
+            &image,
+            |b, image| b.iter(|| recolor.apply(image)),
+        );
+        group.bench_with_input(BenchmarkId::new("theme", &size), &image, |b, image| {
+            b.iter(|| theme_recolor.apply(image))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, recolor);
+criterion_main!(benches);
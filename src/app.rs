@@ -1,82 +1,150 @@
+mod bookmarks;
+mod cache;
 mod comic;
 pub mod config;
+mod download;
+mod find;
+mod history;
+mod pane;
 
+mod prefetch;
+mod print;
+mod search;
+mod search_index;
+mod show;
 mod state;
+mod task;
 mod ui;
 
 use clap::{ArgMatches, ValueEnum};
-use cli_log::info;
-use color_eyre::{Result, eyre::Context};
+use cli_log::{error, info};
+use color_eyre::{
+    Result,
+    eyre::{Context, OptionExt},
+};
 use colors_transform::Color;
-use comic::*;
-use config::Config;
-use crossterm::event::{Event, EventStream, KeyEvent};
-use futures::future::Fuse;
+use comic::{
+    source::{ComicSource, XkcdSource},
+    *,
+};
+use config::{Config, SourceConfig};
+use crossterm::event::{Event, EventStream, KeyCode, KeyEvent, KeyEventKind};
+pub use download::{ComicRange, download};
+pub use find::find;
+use futures::future::FusedFuture;
 use futures::{FutureExt, StreamExt};
-use image::{DynamicImage, Rgb};
-use rand::{rngs::ThreadRng, thread_rng};
+use image::Rgb;
+use pane::{Pane, ScrollPane};
+use prefetch::{Prefetched, Prefetcher};
+pub use print::print;
+use search_index::SearchIndex;
+pub use show::show;
 use state::State;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
-use std::{collections::HashMap, panic};
+use std::{collections::HashMap, mem, panic};
 use strum::{Display, EnumString};
+use task::Task;
 use tokio::time::{Interval, interval};
-use tokio::{select, time};
+use tokio::{select, task::spawn_blocking, time};
 use ui::*;
 
 type Keybindings = HashMap<KeyEvent, CommandToApp>;
 
-type JoinHandle<T> = Fuse<tokio::task::JoinHandle<Result<T>>>;
 pub struct App {
     running: bool,
+    mode: Mode,
     state: State,
-    rng: ThreadRng,
-    process_image: bool,
+    /// The image preset shown, the original image if [`None`]
+    preset: Option<usize>,
+    downloader: Downloader,
     event_stream: EventStream,
     keybindings: Keybindings,
-    xkcd_url: String,
-    explanation_url: String,
     ui: Ui,
     comic: Comic,
-    image_join_handle: JoinHandle<DynamicImage>,
+    prefetcher: Prefetcher,
+    /// Where in the history the comic being switched to is, if it isn't a new entry
+    pending_history_position: Option<usize>,
+    /// The comic being downloaded, relative switches are resolved against it instead of the current
+    /// comic
+    pending_comic: Option<u16>,
+    pane: Option<Pane>,
+    explanation_task: Task<String>,
+    /// Resolves which comic to switch to, before it is downloaded by the comic task
+    comic_number_task: Task<u16>,
+    comic_task: Task<Comic>,
+    image_task: Task<ImageProtocols>,
+    search_index: SearchIndex,
+    search_results: Vec<u16>,
+    index_task: Task<SearchIndex>,
     delete_message_interval: Interval,
 }
 
 const MESSAGE_DURATION: Duration = Duration::from_secs(2);
 const WAIT_DURATION: Duration = Duration::from_millis(100);
 impl App {
-    pub async fn run(cli: ArgMatches) -> Result<()> {
-        let config = Config::new(
-            cli.get_one::<PathBuf>("config_path")
-                .expect("Option has default value"),
-        )
-        .wrap_err("Failed to parse config")?;
-        let mut rng = thread_rng();
-        let mut state = State::new();
-        state.current_comic = get_comic_number(
-            &mut rng,
-            &state,
-            initial_switch_to_comic(config.initial_comic, &cli),
-        )
-        .await?;
-        let comic = download(state.current_comic).await?;
-        let ui = Ui::new(config.styling, config.terminal, config.keep_colors)
+    /// Runs the tui, the initial comic is determined from the cli and config if [`None`]
+    pub async fn run(cli: &ArgMatches, initial_comic: Option<SwitchToComic>) -> Result<()> {
+        let result = Self::try_run(cli, initial_comic).await;
+        ratatui::restore();
+        result
+    }
+
+    async fn try_run(cli: &ArgMatches, initial_comic: Option<SwitchToComic>) -> Result<()> {
+        let config = load_config(cli)?;
+        let downloader = new_downloader(&config, cli)?;
+        let search_index = SearchIndex::load(downloader.source().name())
+            .await
+            .unwrap_or_else(|error| {
+                error!("Failed to load the search index: {error}");
+                SearchIndex::default()
+            });
+        let mut state = State::new(downloader.source().name());
+        state.current_comic = downloader
+            .get_comic_number(
+                &state,
+                initial_comic.unwrap_or_else(|| initial_switch_to_comic(config.initial_comic, cli)),
+            )
+            .await?;
+        let comic = downloader.download(state.current_comic).await?;
+        let seen = state.mark_seen();
+        state.visit();
+        let ui = Ui::new(config.styling, config.terminal, config.image)
             .wrap_err("Failed to initialise ui")
             .and_then(|mut ui| {
-                ui.update(&comic, true, RenderOption::None)?;
+                ui.set_loading(true);
+                ui.set_seen(seen);
+                ui.update(&comic, Some(0), RenderOption::None)?;
                 Ok(ui)
             })?;
+        let mut prefetcher = Prefetcher::new(
+            config.prefetch,
+            downloader.clone(),
+            ui.image_processor().clone(),
+        );
+        prefetcher.prefetch(comic.number());
+        let image_task = spawn_image_download(&downloader, ui.image_processor(), &comic);
         Self {
             state,
-            rng,
-            process_image: true,
+            mode: Mode::Normal,
+            preset: Some(0),
             ui,
-            image_join_handle: tokio::spawn(download_image(comic.image_url().to_string())).fuse(),
+            prefetcher,
+            pending_history_position: None,
+            pending_comic: None,
+            pane: None,
+            explanation_task: Task::terminated(),
+            comic_number_task: Task::terminated(),
+            comic_task: Task::terminated(),
+            image_task,
+            search_index,
+            search_results: Vec::new(),
+            index_task: Task::terminated(),
+            downloader,
             comic,
             event_stream: EventStream::new(),
             keybindings: config.keybindings,
-            xkcd_url: config.url,
-            explanation_url: config.explanation_url,
             running: true,
             delete_message_interval: interval(MESSAGE_DURATION),
         }
@@ -87,9 +155,14 @@ impl App {
     async fn main_loop(mut self) -> Result<()> {
         while self.running {
             select! {
-                    Some(result) = self.event_stream.next().fuse() => {self.handle_crossterm_event(result?).await?}
-                    image_download_result = &mut self.image_join_handle => {self.on_new_image(image_download_result.unwrap())?},
-                    _ = self.delete_message_interval.tick() => self.ui.update(&self.comic, self.process_image, RenderOption::DeleteMessage)?,
+                    Some(result) = self.event_stream.next().fuse() => {self.handle_crossterm_event(result?)?}
+                    comic_number_result = &mut self.comic_number_task => {self.on_comic_number(comic_number_result)?},
+                    comic_download_result = &mut self.comic_task => {self.on_new_comic(comic_download_result)?},
+                    image_download_result = &mut self.image_task => {self.on_new_image(image_download_result)?},
+                    _ = self.prefetcher.next() => {},
+                    index_update_result = &mut self.index_task => {self.on_search_index_updated(index_update_result)?},
+                    explanation_result = &mut self.explanation_task => {self.on_explanation(explanation_result)?},
+                    _ = self.delete_message_interval.tick() => self.ui.update(&self.comic, self.preset, RenderOption::DeleteMessage)?,
                     _ = time::sleep(WAIT_DURATION) => {
                         // Sleep for a short duration to avoid busy waiting.
                     }
@@ -100,30 +173,135 @@ impl App {
         Ok(())
     }
 
-    async fn switch_to_comic(&mut self, switch_to_comic: SwitchToComic) -> Result<()> {
-        let number = get_comic_number(&mut self.rng, &self.state, switch_to_comic).await?;
-        if number != self.state.current_comic {
-            self.state.current_comic = number;
-            let comic = download(number).await?;
-            self.image_join_handle =
-                tokio::spawn(download_image(comic.image_url().to_string())).fuse();
-            self.comic = comic;
-            self.ui.clear_image_protocols();
+    fn switch_to_comic(&mut self, switch_to_comic: SwitchToComic) {
+        self.navigate(switch_to_comic, None);
+    }
+
+    /// Downloads the comic in the background, superseding any comic that is still being downloaded
+    fn navigate(&mut self, switch_to_comic: SwitchToComic, history_position: Option<usize>) {
+        self.pending_history_position = history_position;
+        let mut state = self.state.clone();
+        if let Some(pending_comic) = self.pending_comic {
+            state.current_comic = pending_comic;
+        }
+        // A comic number still being resolved is awaited, so switches in quick succession add up
+        let pending_comic_number = mem::replace(&mut self.comic_number_task, Task::terminated());
+        if pending_comic_number.is_terminated()
+            && let Some(prefetched) = self.prefetcher.take(&state, switch_to_comic)
+        {
+            self.cancel_comic_download();
+            self.set_comic(prefetched.comic, Some(prefetched.image_protocols));
+            return;
+        }
+
+        let downloader = self.downloader.clone();
+        self.comic_number_task = Task::spawn(async move {
+            if !pending_comic_number.is_terminated()
+                && let Ok(number) = pending_comic_number.await
+            {
+                state.current_comic = number;
+            }
+            downloader.get_comic_number(&state, switch_to_comic).await
+        });
+    }
+
+    fn on_comic_number(&mut self, comic_number_result: Result<u16>) -> Result<()> {
+        let render_option = match comic_number_result {
+            Ok(number) if number == self.state.current_comic => {
+                self.cancel_comic_download();
+                RenderOption::None
+            }
+            Ok(number) if Some(number) == self.pending_comic => RenderOption::None,
+            Ok(number) => {
+                match self
+                    .prefetcher
+                    .take(&self.state, SwitchToComic::Specific(number))
+                {
+                    Some(prefetched) => {
+                        self.cancel_comic_download();
+                        self.set_comic(prefetched.comic, Some(prefetched.image_protocols));
+                    }
+                    None => {
+                        let downloader = self.downloader.clone();
+                        self.pending_comic = Some(number);
+                        self.comic_task =
+                            Task::spawn(async move { downloader.download(number).await });
+                    }
+                }
+                RenderOption::None
+            }
+            Err(error) => RenderOption::ShowError(format!("{error:#}")),
         };
-        Ok(())
+
+        self.update_ui(render_option)
+    }
+
+    fn cancel_comic_download(&mut self) {
+        self.pending_comic = None;
+        self.comic_task = Task::terminated();
+    }
+
+    fn on_new_comic(&mut self, comic_download_result: Result<Comic>) -> Result<()> {
+        self.pending_comic = None;
+        let render_option = match comic_download_result {
+            Ok(comic) => {
+                self.set_comic(comic, None);
+                RenderOption::None
+            }
+            Err(error) => RenderOption::ShowError(format!("{error:#}")),
+        };
+
+        self.update_ui(render_option)
+    }
+
+    /// Switches to the comic, downloading its image if it isn't already processed
+    fn set_comic(&mut self, comic: Comic, image_protocols: Option<ImageProtocols>) {
+        self.image_task = match image_protocols {
+            Some(_) => Task::terminated(),
+            None => spawn_image_download(&self.downloader, self.ui.image_processor(), &comic),
+        };
+        let previous_comic = mem::replace(&mut self.comic, comic);
+        if let Some(image_protocols) = self.ui.replace_image_protocols(image_protocols) {
+            self.prefetcher.insert(Prefetched {
+                comic: previous_comic,
+                image_protocols,
+            });
+        }
+        self.state.current_comic = self.comic.number();
+        match self.pending_history_position.take() {
+            Some(position) => self.state.set_history_position(position),
+            None => self.state.visit(),
+        }
+        let seen = self.state.mark_seen();
+        self.ui.set_seen(seen);
+        self.refresh_pane();
+        self.prefetcher.prefetch(self.comic.number());
     }
 
-    fn on_new_image(&mut self, comic_download_result: Result<DynamicImage>) -> Result<()> {
+    fn on_new_image(&mut self, comic_download_result: Result<ImageProtocols>) -> Result<()> {
         let render_option = match comic_download_result {
-            Ok(image) => RenderOption::NewImage(image),
-            Err(error) => RenderOption::ShowError(error.to_string()),
+            Ok(image_protocols) => RenderOption::NewImage(Box::new(image_protocols)),
+            Err(error) => RenderOption::ShowError(format!("{error:#}")),
         };
 
         self.update_ui(render_option)
     }
 
-    async fn handle_crossterm_event(&mut self, event: Event) -> Result<()> {
+    fn handle_crossterm_event(&mut self, event: Event) -> Result<()> {
         let command = match event {
+            Event::Key(key_event) if self.mode != Mode::Normal => {
+                return match (key_event.kind, self.mode) {
+                    (KeyEventKind::Release, _) | (_, Mode::Normal) => Ok(()),
+                    (_, Mode::Bookmarks) => self.handle_bookmarks_key(key_event),
+                    (_, Mode::History) => self.handle_history_key(key_event),
+                    (_, Mode::RenameBookmark(number)) => {
+                        self.handle_rename_bookmark_key(number, key_event)
+                    }
+                    (_, Mode::JumpToComic) => self.handle_jump_to_comic_key(key_event),
+                    (_, Mode::Search) => self.handle_search_key(key_event),
+                    (_, Mode::SearchResults) => self.handle_search_results_key(key_event),
+                };
+            }
             Event::Key(key_event) => match self.keybindings.get(&key_event) {
                 Some(command) => *command,
                 None => return Ok(()),
@@ -132,45 +310,168 @@ impl App {
             _ => return Ok(()),
         };
 
-        self.handle_command(command).await
+        self.handle_command(command)
+    }
+
+    fn edit_prompt(&mut self, key_event: KeyEvent) -> PromptEvent {
+        let Some(prompt) = self.ui.prompt_mut() else {
+            return PromptEvent::Cancel;
+        };
+        match key_event.code {
+            KeyCode::Enter => PromptEvent::Submit(self.ui.close_prompt().unwrap_or_default()),
+            KeyCode::Esc => {
+                self.ui.close_prompt();
+                PromptEvent::Cancel
+            }
+            KeyCode::Backspace => {
+                prompt.text.pop();
+                PromptEvent::Edit
+            }
+            KeyCode::Char(char) => {
+                prompt.text.push(char);
+                PromptEvent::Edit
+            }
+            _ => PromptEvent::Edit,
+        }
     }
 
-    async fn handle_command(&mut self, command: CommandToApp) -> Result<()> {
+    fn handle_jump_to_comic_key(&mut self, key_event: KeyEvent) -> Result<()> {
+        let render_option = match self.edit_prompt(key_event) {
+            PromptEvent::Submit(text) => {
+                self.mode = Mode::Normal;
+                match text.trim().parse() {
+                    Ok(number) => {
+                        self.switch_to_comic(SwitchToComic::Specific(number));
+                        RenderOption::None
+                    }
+                    Err(_) => RenderOption::ShowError(format!("Invalid comic number {text}")),
+                }
+            }
+            PromptEvent::Cancel => {
+                self.mode = Mode::Normal;
+                RenderOption::None
+            }
+            PromptEvent::Edit => RenderOption::None,
+        };
+        self.update_ui(render_option)
+    }
+
+    fn close_popup(&mut self) {
+        self.mode = Mode::Normal;
+        self.ui.close_popup();
+    }
+
+    fn handle_command(&mut self, command: CommandToApp) -> Result<()> {
         let render_option = match command {
             CommandToApp::SwitchToComic(switch_to_comic) => {
-                match self.switch_to_comic(switch_to_comic).await {
-                    Ok(_) => RenderOption::None,
-                    Err(error) => RenderOption::ShowError(error.to_string()),
-                }
+                self.switch_to_comic(switch_to_comic);
+                RenderOption::None
             }
+            CommandToApp::HistoryBack => match self.state.history_back() {
+                Some((position, number)) => {
+                    self.navigate(SwitchToComic::Specific(number), Some(position));
+                    RenderOption::None
+                }
+                None => RenderOption::ShowMessage("No previous comic in history".into()),
+            },
+            CommandToApp::HistoryForward => match self.state.history_forward() {
+                Some((position, number)) => {
+                    self.navigate(SwitchToComic::Specific(number), Some(position));
+                    RenderOption::None
+                }
+                None => RenderOption::ShowMessage("No next comic in history".into()),
+            },
             CommandToApp::ToggleBookmark => {
-                RenderOption::ShowMessage(if self.state.toggle_bookmark() {
-                    "Bookmarked comic"
+                RenderOption::ShowMessage(if self.state.toggle_bookmark(self.comic.name()) {
+                    "Bookmarked comic".into()
                 } else {
-                    "Unbookmarked comic"
+                    "Unbookmarked comic".into()
                 })
             }
-            CommandToApp::OpenInBrowser(open_in_browser) => {
-                self.open_in_browser(open_in_browser)?;
-                RenderOption::ShowMessage(match open_in_browser {
-                    OpenInBrowser::Comic => "Opened comic in your browser!",
-                    OpenInBrowser::Explanation => "Opened explanation in your browser!",
+            CommandToApp::AddBookmark => {
+                RenderOption::ShowMessage(if self.state.add_bookmark(self.comic.name()) {
+                    "Bookmarked comic".into()
+                } else {
+                    "Comic is already bookmarked".into()
+                })
+            }
+            CommandToApp::RemoveBookmark => {
+                RenderOption::ShowMessage(if self.state.remove_bookmark(self.state.current_comic) {
+                    "Unbookmarked comic".into()
+                } else {
+                    "Comic isn't bookmarked".into()
                 })
             }
+            CommandToApp::JumpToComic => {
+                self.mode = Mode::JumpToComic;
+                self.ui
+                    .show_prompt(Prompt::new(" Go to comic ", String::new()));
+                RenderOption::None
+            }
+            CommandToApp::Search => self.show_search_prompt(),
+            CommandToApp::UpdateSearchIndex => self.update_search_index(),
+            CommandToApp::ShowBookmarks => {
+                self.show_bookmarks();
+                RenderOption::None
+            }
+            CommandToApp::ShowHistory => self.show_history(),
+            CommandToApp::OpenInBrowser(open_in_browser) => {
+                match self.open_in_browser(open_in_browser) {
+                    Ok(_) => RenderOption::ShowMessage(match open_in_browser {
+                        OpenInBrowser::Comic => "Opened comic in your browser!".into(),
+                        OpenInBrowser::Explanation => "Opened explanation in your browser!".into(),
+                    }),
+                    Err(error) => RenderOption::ShowError(format!("{error:#}")),
+                }
+            }
             CommandToApp::None => return Ok(()),
             CommandToApp::HandleResize => RenderOption::None,
             CommandToApp::Quit => {
                 self.running = false;
                 return Ok(());
             }
+            CommandToApp::ToggleTranscript => {
+                self.toggle_pane(Pane::Transcript);
+                RenderOption::None
+            }
+            CommandToApp::ToggleExplanation => {
+                self.toggle_pane(Pane::Explanation);
+                RenderOption::None
+            }
+            CommandToApp::Zoom(zoom) => {
+                self.ui.viewport_mut().zoom(zoom);
+                RenderOption::None
+            }
+            CommandToApp::Pan(pan) => {
+                self.ui.viewport_mut().pan(pan);
+                RenderOption::None
+            }
+            CommandToApp::Fit(fit) => {
+                self.ui.viewport_mut().set_fit(fit);
+                RenderOption::None
+            }
+            CommandToApp::ScrollPane(scroll) => {
+                self.scroll_pane(scroll);
+                RenderOption::None
+            }
             CommandToApp::ToggleProcessing => {
-                self.process_image = !self.process_image;
-                RenderOption::ShowMessage(if self.process_image {
-                    "Image processing on"
+                self.preset = match self.preset {
+                    Some(_) => None,
+                    None => Some(0),
+                };
+                RenderOption::ShowMessage(if self.preset.is_some() {
+                    "Image processing on".into()
                 } else {
-                    "Image processing off"
+                    "Image processing off".into()
                 })
             }
+            CommandToApp::CyclePreset => {
+                let image_processor = self.ui.image_processor();
+                self.preset = image_processor.next_preset(self.preset);
+                RenderOption::ShowMessage(
+                    format!("Image preset {}", image_processor.preset_name(self.preset)).into(),
+                )
+            }
         };
         self.update_ui(render_option)
     }
@@ -179,23 +480,77 @@ impl App {
         if let RenderOption::ShowError(_) | RenderOption::ShowMessage(_) = render_option {
             self.delete_message_interval.reset();
         }
-        self.ui
-            .update(&self.comic, self.process_image, render_option)
+        self.ui.set_loading(
+            !self.comic_number_task.is_terminated()
+                || !self.comic_task.is_terminated()
+                || !self.image_task.is_terminated(),
+        );
+        self.ui.update(&self.comic, self.preset, render_option)
     }
 
     fn open_in_browser(&self, open_in_browser: OpenInBrowser) -> Result<()> {
-        open::that(format!(
-            "{}{}",
-            match open_in_browser {
-                OpenInBrowser::Comic => &self.xkcd_url,
-                OpenInBrowser::Explanation => &self.explanation_url,
-            },
-            self.comic.number(),
-        ))?;
+        let source = self.downloader.source();
+        let number = self.comic.number();
+        open::that(match open_in_browser {
+            OpenInBrowser::Comic => source.permalink(number),
+            OpenInBrowser::Explanation => source
+                .explanation_link(number)
+                .ok_or_eyre("This comic source has no explanations")?,
+        })?;
         Ok(())
     }
 }
 
+fn load_config(cli: &ArgMatches) -> Result<Config> {
+    Config::new(
+        cli.get_one::<PathBuf>("config_path")
+            .expect("Option has default value"),
+    )
+    .wrap_err("Failed to parse config")
+}
+
+fn new_downloader(config: &Config, cli: &ArgMatches) -> Result<Downloader> {
+    let source: Arc<dyn ComicSource> = match &config.source {
+        SourceConfig::Xkcd => Arc::new(XkcdSource::new(
+            config.url.clone(),
+            cli.get_one::<String>("api_url")
+                .unwrap_or(&config.api_url)
+                .clone(),
+            config.explanation_url.clone(),
+            config.explanation_api_url.clone(),
+        )),
+        SourceConfig::Json(source) => Arc::new(source.as_ref().clone()),
+        SourceConfig::Rss(source) => Arc::new(source.as_ref().clone()),
+    };
+    Ok(Downloader::new(
+        source,
+        HttpClient::new(&config.network).wrap_err("Failed to create the http client")?,
+        config.offline || cli.get_flag("offline"),
+    ))
+}
+
+fn spawn_image_download(
+    downloader: &Downloader,
+    image_processor: &ImageProcessor,
+    comic: &Comic,
+) -> Task<ImageProtocols> {
+    let (downloader, image_processor) = (downloader.clone(), image_processor.clone());
+    let comic = comic.clone();
+    Task::spawn(async move { download_image_protocols(&downloader, image_processor, &comic).await })
+}
+
+/// Downloads the image and processes it on a blocking thread, as processing big images takes a while
+async fn download_image_protocols(
+    downloader: &Downloader,
+    image_processor: ImageProcessor,
+    comic: &Comic,
+) -> Result<ImageProtocols> {
+    let image = downloader
+        .download_image(comic.number(), comic.image_url().to_string())
+        .await?;
+    Ok(spawn_blocking(move || image_processor.image_protocols(image)).await?)
+}
+
 fn initial_switch_to_comic(default: SwitchToComic, cli: &ArgMatches) -> SwitchToComic {
     cli.get_one::<u16>("number")
         .map(|num| SwitchToComic::Specific(num.to_owned()))
@@ -209,14 +564,53 @@ enum CommandToApp {
     Quit,
     #[strum(disabled)]
     SwitchToComic(SwitchToComic),
+    HistoryBack,
+    HistoryForward,
+    JumpToComic,
+    Search,
+    UpdateSearchIndex,
+    CyclePreset,
+    /// Switches between the first preset and the original image, from before there were presets
     ToggleProcessing,
     ToggleBookmark,
+    AddBookmark,
+    RemoveBookmark,
+    ShowBookmarks,
+    ShowHistory,
+    ToggleTranscript,
+    ToggleExplanation,
+    #[strum(disabled)]
+    ScrollPane(ScrollPane),
+    #[strum(disabled)]
+    Zoom(Zoom),
+    #[strum(disabled)]
+    Pan(Pan),
+    #[strum(disabled)]
+    Fit(Fit),
     #[strum(disabled)]
     OpenInBrowser(OpenInBrowser),
     HandleResize,
     None,
 }
 
+/// Decides where key events go
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Mode {
+    Normal,
+    Bookmarks,
+    RenameBookmark(u16),
+    History,
+    JumpToComic,
+    Search,
+    SearchResults,
+}
+
+enum PromptEvent {
+    Submit(String),
+    Cancel,
+    Edit,
+}
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, EnumString)]
 #[strum(serialize_all = "snake_case")]
 enum OpenInBrowser {
@@ -236,6 +630,13 @@ pub enum SwitchToComic {
     Random,
     Bookmarked,
     #[clap(skip)]
+    NextBookmark,
+    #[clap(skip)]
+    PreviousBookmark,
+    #[clap(skip)]
+    NextUnread,
+    RandomUnread,
+    #[clap(skip)]
     Specific(u16),
     LastSeen,
 }
@@ -0,0 +1,109 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::text::{Line, Span};
+
+use super::{App, Mode, PromptEvent, SwitchToComic, ui::*};
+
+const HELP: &str = " enter: open | r: rename | d: delete | esc: close ";
+
+impl App {
+    pub(super) fn show_bookmarks(&mut self) {
+        self.mode = Mode::Bookmarks;
+        self.ui.show_popup(ListPopup::new(
+            " Bookmarks ".to_string(),
+            HELP,
+            self.bookmark_rows(),
+        ));
+    }
+
+    pub(super) fn handle_bookmarks_key(&mut self, key_event: KeyEvent) -> Result<()> {
+        let Some(popup) = self.ui.popup_mut() else {
+            return Ok(());
+        };
+        let selected = popup
+            .selected()
+            .and_then(|index| self.state.bookmarks().keys().nth(index).copied());
+        let render_option = match (key_event.code, selected) {
+            (KeyCode::Up | KeyCode::Char('k'), _) => {
+                popup.select_previous();
+                RenderOption::None
+            }
+            (KeyCode::Down | KeyCode::Char('j'), _) => {
+                popup.select_next();
+                RenderOption::None
+            }
+            (KeyCode::Enter, Some(number)) => {
+                self.close_popup();
+                self.switch_to_comic(SwitchToComic::Specific(number));
+                RenderOption::None
+            }
+            (KeyCode::Char('r'), Some(number)) => {
+                let name = self.state.bookmarks()[&number].name.clone();
+                self.mode = Mode::RenameBookmark(number);
+                self.ui
+                    .show_prompt(Prompt::new(" Bookmark name ", name.unwrap_or_default()));
+                RenderOption::None
+            }
+            (KeyCode::Char('d') | KeyCode::Delete, Some(number)) => {
+                self.state.remove_bookmark(number);
+                self.refresh_bookmarks();
+                RenderOption::ShowMessage("Unbookmarked comic".into())
+            }
+            (KeyCode::Esc | KeyCode::Char('q'), _) => {
+                self.close_popup();
+                RenderOption::None
+            }
+            _ => return Ok(()),
+        };
+        self.update_ui(render_option)
+    }
+
+    pub(super) fn handle_rename_bookmark_key(
+        &mut self,
+        number: u16,
+        key_event: KeyEvent,
+    ) -> Result<()> {
+        match self.edit_prompt(key_event) {
+            PromptEvent::Submit(name) => {
+                let name = Some(name.trim().to_string()).filter(|name| !name.is_empty());
+                self.state.rename_bookmark(number, name);
+                self.refresh_bookmarks();
+                self.mode = Mode::Bookmarks;
+            }
+            PromptEvent::Cancel => self.mode = Mode::Bookmarks,
+            PromptEvent::Edit => {}
+        }
+        self.update_ui(RenderOption::None)
+    }
+
+    fn refresh_bookmarks(&mut self) {
+        let rows = self.bookmark_rows();
+        if let Some(popup) = self.ui.popup_mut() {
+            popup.set_rows(rows);
+        }
+    }
+
+    fn bookmark_rows(&self) -> Vec<Line<'static>> {
+        let styling = self.ui.styling_config();
+        self.state
+            .bookmarks()
+            .iter()
+            .map(|(number, bookmark)| {
+                let mut spans = vec![
+                    Span::styled(
+                        format!("{number:>5} {} ", bookmark.title),
+                        styling.title_style,
+                    ),
+                    Span::styled(
+                        bookmark.created.format("%Y-%m-%d").to_string(),
+                        styling.date_style,
+                    ),
+                ];
+                if let Some(name) = &bookmark.name {
+                    spans.push(Span::styled(format!(" {name}"), styling.messages_style));
+                }
+                Line::from(spans)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,138 @@
+use cli_log::error;
+use color_eyre::Result;
+use std::{
+    collections::BTreeSet,
+    io::ErrorKind,
+    path::PathBuf,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use tap::Tap;
+use tokio::fs;
+
+use super::state::get_path_to_data_dir;
+
+const JSON_DIR: &str = "json";
+const IMAGES_DIR: &str = "images";
+/// The directory of the source itself
+const SOURCE_DIR: &str = ".";
+const INDEX_FILE: &str = "index.json";
+
+pub async fn read_json(source: &str, number: u16) -> Result<Option<String>> {
+    read(source, JSON_DIR, json_file_name(number))
+        .await
+        .map(|bytes| bytes.map(|bytes| String::from_utf8_lossy(&bytes).into_owned()))
+}
+
+pub async fn write_json(source: &str, number: u16, json: &str) {
+    write(source, JSON_DIR, json_file_name(number), json.as_bytes()).await
+}
+
+pub async fn remove_json(source: &str, number: u16) {
+    remove(source, JSON_DIR, json_file_name(number)).await
+}
+
+pub async fn read_image(source: &str, number: u16) -> Result<Option<Vec<u8>>> {
+    read(source, IMAGES_DIR, number.to_string()).await
+}
+
+pub async fn write_image(source: &str, number: u16, bytes: &[u8]) {
+    write(source, IMAGES_DIR, number.to_string(), bytes).await
+}
+
+pub async fn remove_image(source: &str, number: u16) {
+    remove(source, IMAGES_DIR, number.to_string()).await
+}
+
+pub async fn read_index(source: &str) -> Result<Option<Vec<u8>>> {
+    read(source, SOURCE_DIR, INDEX_FILE.to_string()).await
+}
+
+pub async fn write_index(source: &str, index: &[u8]) {
+    write(source, SOURCE_DIR, INDEX_FILE.to_string(), index).await
+}
+
+/// Numbers of all comics whose json is cached
+pub async fn cached_numbers(source: &str) -> Result<BTreeSet<u16>> {
+    numbers_in_dir(source, JSON_DIR).await
+}
+
+/// Numbers of all comics whose image is cached
+pub async fn cached_image_numbers(source: &str) -> Result<BTreeSet<u16>> {
+    numbers_in_dir(source, IMAGES_DIR).await
+}
+
+async fn numbers_in_dir(source: &str, dir: &str) -> Result<BTreeSet<u16>> {
+    let mut numbers = BTreeSet::new();
+    let mut entries = match fs::read_dir(get_path_to_cache_dir(source, dir)?).await {
+        Ok(entries) => entries,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(numbers),
+        Err(error) => return Err(error.into()),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        if let Some(number) = entry
+            .path()
+            .file_stem()
+            .and_then(|stem| stem.to_str()?.parse().ok())
+        {
+            numbers.insert(number);
+        }
+    }
+    Ok(numbers)
+}
+
+async fn read(source: &str, dir: &str, file_name: String) -> Result<Option<Vec<u8>>> {
+    match fs::read(get_path_to_cache_dir(source, dir)?.tap_mut(|path| path.push(file_name))).await {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+/// Failing to write to the cache shouldn't prevent the comic from being shown, so errors are only logged.
+/// The file is written next to the entry and renamed over it, so an interrupted write can't leave a
+/// truncated entry
+async fn write(source: &str, dir: &str, file_name: String, bytes: &[u8]) {
+    // Tells apart concurrent writes of the same entry
+    static WRITES: AtomicUsize = AtomicUsize::new(0);
+    let result = async {
+        let dir = get_path_to_cache_dir(source, dir)?;
+        fs::create_dir_all(&dir).await?;
+        let temporary_path = dir.join(format!(
+            ".{file_name}.{}.tmp",
+            WRITES.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temporary_path, bytes).await?;
+        if let Err(error) = fs::rename(&temporary_path, dir.join(&file_name)).await {
+            let _ = fs::remove_file(temporary_path).await;
+            return Err(error.into());
+        }
+        Result::<()>::Ok(())
+    }
+    .await;
+    if let Err(error) = result {
+        error!("Failed to write {file_name} to the cache: {error}")
+    }
+}
+
+/// Removes an entry that turned out to be broken, errors are only logged like when writing
+async fn remove(source: &str, dir: &str, file_name: String) {
+    let result = async {
+        let path = get_path_to_cache_dir(source, dir)?.tap_mut(|path| path.push(&file_name));
+        match fs::remove_file(path).await {
+            Err(error) if error.kind() != ErrorKind::NotFound => Err(error.into()),
+            _ => Result::<()>::Ok(()),
+        }
+    }
+    .await;
+    if let Err(error) = result {
+        error!("Failed to remove {file_name} from the cache: {error}")
+    }
+}
+
+fn json_file_name(number: u16) -> String {
+    format!("{number}.json")
+}
+
+fn get_path_to_cache_dir(source: &str, dir: &str) -> Result<PathBuf> {
+    Ok(get_path_to_data_dir()?.tap_mut(|path| path.extend(["cache", source, dir])))
+}
@@ -1,53 +1,64 @@
+mod error;
+mod http;
+pub mod source;
+
 use crate::app::SwitchToComic::{self, *};
-use color_eyre::{Result, eyre::Context};
+use cli_log::error;
+use color_eyre::{
+    Report, Result,
+    eyre::{Context, OptionExt, eyre},
+};
+pub use error::ComicError;
+pub use http::HttpClient;
 use image::DynamicImage;
-use isahc::AsyncReadResponseExt;
-use rand::{Rng, rngs::ThreadRng};
-use serde_json::Value;
-use std::{fmt::Display, ops::Sub};
+use isahc::http::StatusCode;
+use rand::{Rng, seq::IteratorRandom, thread_rng};
+use source::ComicSource;
+use std::{
+    fmt::Display,
+    ops::Sub,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use super::state::State;
+use super::{cache, state::State};
 
 #[derive(Clone)]
 pub struct Comic {
     name: String,
+    safe_name: String,
     number: u16,
     alt_text: String,
+    transcript: String,
     date_uploaded: String,
     interactive: bool,
     image_url: String,
+    link: String,
+    news: String,
 }
 
 impl Comic {
-    fn new(json: Value) -> Option<Self> {
-        let alt_text = json["alt"].as_str()?.to_string();
-        let name = json["title"].as_str()?.to_string();
-        let date_uploaded = format!(
-            "{}-{:02}-{:02}",
-            json["year"].as_str()?,
-            json["month"].as_str()?.parse::<u16>().ok()?,
-            json["day"].as_str()?.parse::<u16>().ok()?,
-        );
-        let image_url = json["img"].as_str()?.to_string();
-        let number = json["num"].as_u64()? as u16;
-        Some(Self {
-            name,
-            number,
-            alt_text,
-            date_uploaded,
-            interactive: !json["extra_parts"].is_null(),
-            image_url,
-        })
-    }
-
     pub fn number(&self) -> u16 {
         self.number
     }
 
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The name without html or unicode, same as the name for most comics
+    pub fn safe_name(&self) -> &str {
+        &self.safe_name
+    }
+
     pub fn alt_text(&self) -> &str {
         &self.alt_text
     }
 
+    pub fn transcript(&self) -> &str {
+        &self.transcript
+    }
+
     pub fn date_uploaded(&self) -> &str {
         &self.date_uploaded
     }
@@ -55,6 +66,20 @@ impl Comic {
     pub fn image_url(&self) -> &str {
         &self.image_url
     }
+
+    pub fn interactive(&self) -> bool {
+        self.interactive
+    }
+
+    /// Where clicking the comic leads, empty for most comics
+    pub fn link(&self) -> &str {
+        &self.link
+    }
+
+    /// Announcements shown below the comic, empty for most comics
+    pub fn news(&self) -> &str {
+        &self.news
+    }
 }
 
 impl Display for Comic {
@@ -68,60 +93,298 @@ impl Display for Comic {
     }
 }
 
-pub async fn download(comic: u16) -> Result<Comic> {
-    let json = download_json(Some(comic)).await?;
-    Ok(Comic::new(json).expect("XKCD should always return valid json"))
-}
+/// How long the latest comic number is reused, so switching to the next comic and prefetching the
+/// ones after it don't each request it
+const LATEST_NUMBER_LIFETIME: Duration = Duration::from_secs(60);
 
-pub async fn download_image(image_url: String) -> Result<DynamicImage> {
-    let bytes = &isahc::get_async(image_url).await?.bytes().await?;
-    Ok(image::load_from_memory(bytes)?)
+#[derive(Clone)]
+pub struct Downloader {
+    source: Arc<dyn ComicSource>,
+    client: HttpClient,
+    offline: bool,
+    /// The latest comic number and when it was fetched, shared by the clones
+    latest_number: Arc<Mutex<Option<(Instant, u16)>>>,
 }
 
-pub async fn get_comic_number(
-    rng: &mut ThreadRng,
-    state: &State,
-    switch_to_comic: SwitchToComic,
-) -> Result<u16> {
-    Ok(match switch_to_comic {
-        Next => {
-            if get_latest_comic_number().await? > state.current_comic {
-                state.current_comic + 1
-            } else {
-                state.current_comic
+impl Downloader {
+    pub fn new(source: Arc<dyn ComicSource>, client: HttpClient, offline: bool) -> Self {
+        Self {
+            source,
+            client,
+            offline,
+            latest_number: Arc::default(),
+        }
+    }
+
+    pub fn source(&self) -> &dyn ComicSource {
+        self.source.as_ref()
+    }
+
+    pub fn offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Cached metadata that fails to parse is downloaded again when online
+    pub async fn download(&self, comic: u16) -> Result<Comic> {
+        let source = self.source.name();
+        match cache::read_json(source, comic).await? {
+            Some(text) => match self.source.parse_metadata(&text) {
+                Ok(comic) => return Ok(comic),
+                Err(error) if self.offline => return Err(error.into()),
+                Err(error) => {
+                    error!(
+                        "The cached metadata of comic {comic} is broken, downloading it again: {error}"
+                    );
+                    cache::remove_json(source, comic).await;
+                }
+            },
+            None if self.offline => return Err(not_cached_error(comic)),
+            None => {}
+        }
+        let text = self.download_json(Some(comic)).await?;
+        Ok(self.source.parse_metadata(&text)?)
+    }
+
+    pub async fn download_image(&self, comic: u16, image_url: String) -> Result<DynamicImage> {
+        Ok(self.download_decoded_image(comic, image_url).await?.1)
+    }
+
+    /// Downloads the encoded image and caches it
+    pub async fn download_image_bytes(&self, comic: u16, image_url: String) -> Result<Vec<u8>> {
+        Ok(self.download_decoded_image(comic, image_url).await?.0)
+    }
+
+    /// Only caches bytes that decode, as a captive portal answers with a page instead of the image.
+    /// A cached image that fails to decode is downloaded again when online
+    async fn download_decoded_image(
+        &self,
+        comic: u16,
+        image_url: String,
+    ) -> Result<(Vec<u8>, DynamicImage)> {
+        let source = self.source.name();
+        match cache::read_image(source, comic).await? {
+            Some(bytes) => match image::load_from_memory(&bytes) {
+                Ok(image) => return Ok((bytes, image)),
+                Err(error) if self.offline => {
+                    return Err(eyre!(
+                        "The cached image of comic {comic} is broken: {error}"
+                    ));
+                }
+                Err(error) => {
+                    error!(
+                        "The cached image of comic {comic} is broken, downloading it again: {error}"
+                    );
+                    cache::remove_image(source, comic).await;
+                }
+            },
+            None if self.offline => return Err(not_cached_error(comic)),
+            None => {}
+        }
+        let bytes = self.client.get(&image_url).await?;
+        let image = image::load_from_memory(&bytes)
+            .wrap_err_with(|| format!("{image_url} didn't return an image"))?;
+        cache::write_image(source, comic, &bytes).await;
+        Ok((bytes, image))
+    }
+
+    /// Downloads the wikitext of the explanation of the comic
+    pub async fn download_explanation(&self, comic: u16) -> Result<String> {
+        let url = self
+            .source
+            .explanation_api_url(comic)
+            .ok_or_eyre("This comic source has no explanations")?;
+        if self.offline {
+            return Err(eyre!("Explanations can't be downloaded offline"));
+        }
+        let json: serde_json::Value =
+            serde_json::from_slice(&self.client.get(&url).await?).map_err(ComicError::from)?;
+        if let Some(error) = json["error"]["info"].as_str() {
+            return Err(eyre!("Failed to get the explanation: {error}"));
+        }
+        // The wikitext is nested in an object unless formatversion=2 is requested
+        let wikitext = &json["parse"]["wikitext"];
+        wikitext
+            .as_str()
+            .or_else(|| wikitext["*"].as_str())
+            .map(str::to_string)
+            .ok_or_else(|| ComicError::MissingField("parse.wikitext".to_string()).into())
+    }
+
+    pub async fn get_comic_number(
+        &self,
+        state: &State,
+        switch_to_comic: SwitchToComic,
+    ) -> Result<u16> {
+        if self.offline {
+            return get_cached_comic_number(self.source.name(), state, switch_to_comic).await;
+        }
+
+        Ok(match switch_to_comic {
+            Next => {
+                if self.get_latest_comic_number().await? > state.current_comic {
+                    state.current_comic + 1
+                } else {
+                    state.current_comic
+                }
+            }
+            Previous => state.current_comic.sub(1).max(1),
+            Latest => self.get_latest_comic_number().await?,
+            First => 1,
+            Random => {
+                let latest = self.get_latest_comic_number().await?;
+                thread_rng().gen_range(1..=latest.max(1))
+            }
+            Bookmarked => state.bookmarked_comic().unwrap_or(state.current_comic),
+            NextBookmark => state.next_bookmark().unwrap_or(state.current_comic),
+            PreviousBookmark => state.previous_bookmark().unwrap_or(state.current_comic),
+            NextUnread => {
+                let latest = self.get_latest_comic_number().await?;
+                (state.current_comic + 1..=latest)
+                    .chain(1..state.current_comic)
+                    .find(|number| !state.is_seen(*number))
+                    .ok_or_eyre(ALL_READ)?
+            }
+            RandomUnread => {
+                let latest = self.get_latest_comic_number().await?;
+                (1..=latest)
+                    .filter(|number| !state.is_seen(*number))
+                    .choose(&mut thread_rng())
+                    .ok_or_eyre(ALL_READ)?
+            }
+            Specific(num) => self.validate_comic_number(num).await?,
+            LastSeen => state.current_comic,
+        })
+    }
+
+    async fn validate_comic_number(&self, number: u16) -> Result<u16> {
+        if number == 0 {
+            return Err(eyre!("Comic numbers start at 1"));
+        }
+        if cache::read_json(self.source.name(), number)
+            .await?
+            .is_some()
+        {
+            return Ok(number);
+        }
+        let latest = self.get_latest_comic_number().await?;
+        if number > latest {
+            return Err(eyre!(
+                "Comic {number} doesn't exist, the latest is {latest}"
+            ));
+        }
+        Ok(number)
+    }
+
+    pub async fn get_latest_comic_number(&self) -> Result<u16> {
+        if let Some((fetched, number)) = *self.latest_number.lock().unwrap()
+            && fetched.elapsed() < LATEST_NUMBER_LIFETIME
+        {
+            return Ok(number);
+        }
+        let text = self
+            .download_json(None)
+            .await
+            .wrap_err("Failed to determine latest comic number")?;
+        let comic = self
+            .source
+            .parse_metadata(&text)
+            .wrap_err("Failed to determine latest comic number")?;
+        *self.latest_number.lock().unwrap() = Some((Instant::now(), comic.number));
+        Ok(comic.number)
+    }
+
+    /// Downloads the metadata of the given comic, or the latest comic if [`None`], and caches it.
+    /// Every comic of a document describing several is cached
+    async fn download_json(&self, number: Option<u16>) -> Result<String, ComicError> {
+        let url = match number {
+            Some(number) => self.source.metadata_url(number),
+            None => self.source.latest_metadata_url(),
+        };
+        let text = match self.client.get(&url).await {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+            Err(ComicError::HttpStatus {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }) if let Some(number) = number => return Err(ComicError::NotFound(number)),
+            Err(error) => return Err(error),
+        };
+
+        let documents = self.source.split_metadata(text)?;
+        let is_single = documents.len() == 1;
+        let mut wanted: Option<(u16, String)> = None;
+        for document in documents {
+            let Ok(comic) = self.source.parse_metadata(&document) else {
+                // Returned anyway, so the caller reports why it doesn't parse
+                if is_single {
+                    return Ok(document);
+                }
+                continue;
+            };
+            cache::write_json(self.source.name(), comic.number, &document).await;
+            let is_wanted = match number {
+                Some(number) => comic.number == number,
+                None => wanted
+                    .as_ref()
+                    .is_none_or(|(latest, _)| comic.number > *latest),
+            };
+            if is_wanted {
+                wanted = Some((comic.number, document));
             }
         }
-        Previous => state.current_comic.sub(1).max(1),
-        Latest => get_latest_comic_number().await?,
-        First => 1,
-        Random => {
-            let latest = get_latest_comic_number().await?;
-            rng.gen_range(1..latest)
+        match (wanted, number) {
+            (Some((_, document)), _) => Ok(document),
+            (None, Some(number)) => Err(ComicError::NotFound(number)),
+            (None, None) => Err(ComicError::MissingField("item".to_string())),
         }
-        Bookmarked => state.bookmarked_comic().unwrap_or(state.current_comic),
-        Specific(num) => num,
-        LastSeen => state.current_comic,
-    })
+    }
 }
 
-async fn get_latest_comic_number() -> Result<u16> {
-    let json = download_json(None)
-        .await
-        .wrap_err("Failed to determine latest comic number")?;
-    let num = json["num"]
-        .as_u64()
-        .expect("XKCD should always return valid json") as u16;
-    Ok(num)
+/// Resolves the comic number using only the comics in the cache
+async fn get_cached_comic_number(
+    source: &str,
+    state: &State,
+    switch_to_comic: SwitchToComic,
+) -> Result<u16> {
+    let cached = cache::cached_numbers(source).await?;
+    let current = state.current_comic;
+    let number = match switch_to_comic {
+        Next => cached.range(current + 1..).next().copied(),
+        Previous => cached.range(..current).next_back().copied(),
+        Latest => cached.last().copied(),
+        First => cached.first().copied(),
+        Random => cached.iter().choose(&mut thread_rng()).copied(),
+        Bookmarked => Some(state.bookmarked_comic().unwrap_or(current)),
+        NextBookmark => Some(state.next_bookmark().unwrap_or(current)),
+        PreviousBookmark => Some(state.previous_bookmark().unwrap_or(current)),
+        NextUnread => Some(
+            cached
+                .range(current + 1..)
+                .chain(cached.range(..current))
+                .find(|number| !state.is_seen(**number))
+                .copied()
+                .ok_or_eyre(ALL_READ)?,
+        ),
+        RandomUnread => Some(
+            cached
+                .iter()
+                .filter(|number| !state.is_seen(**number))
+                .choose(&mut thread_rng())
+                .copied()
+                .ok_or_eyre(ALL_READ)?,
+        ),
+        Specific(num) => Some(num),
+        LastSeen => Some(current),
+    };
+
+    match number {
+        Some(number) => Ok(number),
+        None if cached.is_empty() => Err(eyre!("No comics are cached")),
+        None => Ok(current),
+    }
 }
 
-async fn download_json(number: Option<u16>) -> Result<Value> {
-    let text = isahc::get_async(match number {
-        Some(number) => format!("https://xkcd.com/{number}/info.0.json"),
-        _ => String::from("https://xkcd.com/info.0.json"),
-    })
-    .await?
-    .text()
-    .await?;
+const ALL_READ: &str = "You have read every comic";
 
-    Ok(serde_json::from_str(&text)?)
+fn not_cached_error(comic: u16) -> Report {
+    eyre!("Comic {comic} is not cached")
 }
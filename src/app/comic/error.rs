@@ -0,0 +1,23 @@
+use isahc::http::StatusCode;
+use thiserror::Error;
+
+/// Why a comic couldn't be downloaded or read
+#[derive(Debug, Error)]
+pub enum ComicError {
+    #[error("Failed to reach {url}")]
+    Network {
+        url: String,
+        #[source]
+        source: isahc::Error,
+    },
+    #[error("{url} responded with {status}")]
+    HttpStatus { url: String, status: StatusCode },
+    #[error("Comic {0} doesn't exist")]
+    NotFound(u16),
+    #[error("The metadata isn't valid json: {0}")]
+    Parse(#[from] serde_json::Error),
+    #[error("The feed isn't valid xml: {0}")]
+    Feed(#[from] roxmltree::Error),
+    #[error("The metadata has no valid {0} field")]
+    MissingField(String),
+}
@@ -0,0 +1,132 @@
+use color_eyre::{Result, eyre::Context};
+use futures::AsyncReadExt;
+use isahc::{
+    AsyncBody, Request, Response,
+    config::{Configurable, RedirectPolicy},
+    http::{StatusCode, header::USER_AGENT},
+};
+use std::{sync::Arc, time::Duration};
+use tokio::{sync::Semaphore, time};
+
+use super::ComicError;
+use crate::app::config::NetworkConfig;
+
+const DEFAULT_USER_AGENT: &str = concat!("oxikcde/", env!("CARGO_PKG_VERSION"));
+/// Transfers slower than this for the read timeout are aborted, in bytes per second
+const LOW_SPEED_LIMIT: u32 = 1;
+/// The longest the exponential backoff waits before a retry
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// The client every request goes through, applying the network config
+#[derive(Clone)]
+pub struct HttpClient {
+    client: isahc::HttpClient,
+    retries: u32,
+    retry_delay: Duration,
+    permits: Arc<Semaphore>,
+}
+
+impl HttpClient {
+    pub fn new(config: &NetworkConfig) -> Result<Self> {
+        let mut builder = isahc::HttpClient::builder()
+            .connect_timeout(Duration::from_secs(config.connect_timeout))
+            .low_speed_timeout(LOW_SPEED_LIMIT, Duration::from_secs(config.read_timeout))
+            .redirect_policy(RedirectPolicy::Limit(10))
+            .default_header(
+                USER_AGENT,
+                config.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT),
+            );
+        if let Some(proxy) = &config.proxy {
+            builder = builder.proxy(Some(
+                proxy
+                    .parse()
+                    .wrap_err_with(|| format!("Invalid proxy url {proxy}"))?,
+            ));
+        }
+        Ok(Self {
+            client: builder.build()?,
+            retries: config.retries,
+            retry_delay: Duration::from_millis(config.retry_delay),
+            permits: Arc::new(Semaphore::new(config.max_concurrent_requests.get())),
+        })
+    }
+
+    /// Downloads the body, retrying with an exponential backoff on network errors and server errors
+    pub async fn get(&self, url: &str) -> Result<Vec<u8>, ComicError> {
+        let mut attempt = 0;
+        loop {
+            match self.try_get(url).await {
+                Err(error) if attempt < self.retries && is_transient(&error) => {
+                    time::sleep(backoff(self.retry_delay, attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn try_get(&self, url: &str) -> Result<Vec<u8>, ComicError> {
+        let _permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("The semaphore is never closed");
+        let network_error = |source| ComicError::Network {
+            url: url.to_string(),
+            source,
+        };
+        let request = Request::get(url)
+            .body(())
+            .map_err(|error| network_error(error.into()))?;
+        let response: Response<AsyncBody> = self
+            .client
+            .send_async(request)
+            .await
+            .map_err(network_error)?;
+        if !response.status().is_success() {
+            return Err(ComicError::HttpStatus {
+                url: url.to_string(),
+                status: response.status(),
+            });
+        }
+        let mut bytes = Vec::new();
+        response
+            .into_body()
+            .read_to_end(&mut bytes)
+            .await
+            .map_err(|error| network_error(error.into()))?;
+        Ok(bytes)
+    }
+}
+
+fn is_transient(error: &ComicError) -> bool {
+    match error {
+        ComicError::Network { .. } => true,
+        ComicError::HttpStatus { status, .. } => {
+            status.is_server_error() || *status == StatusCode::TOO_MANY_REQUESTS
+        }
+        _ => false,
+    }
+}
+
+/// The delay doubles with every attempt, up to [`MAX_RETRY_DELAY`]
+fn backoff(retry_delay: Duration, attempt: u32) -> Duration {
+    retry_delay
+        .saturating_mul(2u32.checked_pow(attempt).unwrap_or(u32::MAX))
+        .min(MAX_RETRY_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_is_bounded() {
+        let delay = Duration::from_millis(500);
+        assert_eq!(backoff(delay, 0), delay);
+        assert_eq!(backoff(delay, 3), Duration::from_secs(4));
+        assert_eq!(backoff(delay, 20), MAX_RETRY_DELAY);
+        assert_eq!(backoff(delay, 40), MAX_RETRY_DELAY);
+        assert_eq!(backoff(Duration::MAX, 1), MAX_RETRY_DELAY);
+    }
+}
@@ -0,0 +1,36 @@
+mod json;
+mod rss;
+mod xkcd;
+
+pub use json::JsonSource;
+pub use rss::RssSource;
+pub use xkcd::XkcdSource;
+
+use super::{Comic, ComicError};
+
+/// A numbered webcomic. The sources only describe where the comic lives and how to read its metadata,
+/// fetching and caching is done by the [`Downloader`](super::Downloader)
+pub trait ComicSource: Send + Sync {
+    /// Used to separate the caches of different sources
+    fn name(&self) -> &str;
+
+    fn latest_metadata_url(&self) -> String;
+
+    fn metadata_url(&self, number: u16) -> String;
+
+    /// Splits a document describing several comics, like a feed, into the metadata of every comic.
+    /// Sources with a document per comic keep it as it is
+    fn split_metadata(&self, text: String) -> Result<Vec<String>, ComicError> {
+        Ok(vec![text])
+    }
+
+    /// Fails if the metadata doesn't describe a comic
+    fn parse_metadata(&self, text: &str) -> Result<Comic, ComicError>;
+
+    fn permalink(&self, number: u16) -> String;
+
+    fn explanation_link(&self, number: u16) -> Option<String>;
+
+    /// Where the wikitext of the explanation is fetched from, a MediaWiki parse api request
+    fn explanation_api_url(&self, number: u16) -> Option<String>;
+}
@@ -0,0 +1,108 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{Comic, ComicError, ComicSource};
+
+const NUMBER_PLACEHOLDER: &str = "{number}";
+
+/// A comic exposing its metadata as json. The fields are located with json pointers, and
+/// `{number}` in the urls is replaced with the comic number
+#[derive(Deserialize, Clone)]
+pub struct JsonSource {
+    name: String,
+    latest_url: String,
+    metadata_url: String,
+    permalink_url: String,
+    explanation_url: Option<String>,
+    explanation_api_url: Option<String>,
+
+    number_field: String,
+    title_field: String,
+    image_field: String,
+    alt_text_field: Option<String>,
+    date_field: Option<String>,
+    transcript_field: Option<String>,
+    link_field: Option<String>,
+    news_field: Option<String>,
+}
+
+impl JsonSource {
+    fn field<'a>(json: &'a Value, pointer: &Option<String>) -> Option<&'a str> {
+        json.pointer(pointer.as_deref()?)?.as_str()
+    }
+
+    fn required_field<'a>(json: &'a Value, pointer: &str) -> Result<&'a str, ComicError> {
+        json.pointer(pointer)
+            .and_then(Value::as_str)
+            .ok_or_else(|| ComicError::MissingField(pointer.to_string()))
+    }
+}
+
+impl ComicSource for JsonSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn latest_metadata_url(&self) -> String {
+        self.latest_url.clone()
+    }
+
+    fn metadata_url(&self, number: u16) -> String {
+        self.metadata_url
+            .replace(NUMBER_PLACEHOLDER, &number.to_string())
+    }
+
+    fn parse_metadata(&self, text: &str) -> Result<Comic, ComicError> {
+        let json: Value = serde_json::from_str(text)?;
+        let number = match json.pointer(&self.number_field) {
+            Some(Value::String(number)) => number.parse().ok(),
+            Some(number) => number.as_u64().and_then(|number| number.try_into().ok()),
+            None => None,
+        }
+        .ok_or_else(|| ComicError::MissingField(self.number_field.clone()))?;
+        let name = Self::required_field(&json, &self.title_field)?.to_string();
+        Ok(Comic {
+            safe_name: name.clone(),
+            name,
+            number,
+            alt_text: Self::field(&json, &self.alt_text_field)
+                .unwrap_or_default()
+                .to_string(),
+            transcript: Self::field(&json, &self.transcript_field)
+                .unwrap_or_default()
+                .to_string(),
+            date_uploaded: Self::field(&json, &self.date_field)
+                .unwrap_or_default()
+                .to_string(),
+            interactive: false,
+            image_url: Self::required_field(&json, &self.image_field)?.to_string(),
+            link: Self::field(&json, &self.link_field)
+                .unwrap_or_default()
+                .to_string(),
+            news: Self::field(&json, &self.news_field)
+                .unwrap_or_default()
+                .to_string(),
+        })
+    }
+
+    fn permalink(&self, number: u16) -> String {
+        self.permalink_url
+            .replace(NUMBER_PLACEHOLDER, &number.to_string())
+    }
+
+    fn explanation_link(&self, number: u16) -> Option<String> {
+        Some(
+            self.explanation_url
+                .as_ref()?
+                .replace(NUMBER_PLACEHOLDER, &number.to_string()),
+        )
+    }
+
+    fn explanation_api_url(&self, number: u16) -> Option<String> {
+        Some(
+            self.explanation_api_url
+                .as_ref()?
+                .replace(NUMBER_PLACEHOLDER, &number.to_string()),
+        )
+    }
+}
@@ -0,0 +1,222 @@
+use chrono::DateTime;
+use roxmltree::{Document, Node};
+use serde::{Deserialize, Serialize};
+
+use super::{Comic, ComicError, ComicSource};
+
+const NUMBER_PLACEHOLDER: &str = "{number}";
+
+/// A comic publishing an RSS feed. The comic number is read from the link of every item by
+/// matching it against the permalink url, where `{number}` stands for the number. Feeds only list
+/// the recent comics, so older ones can only be read from the cache
+#[derive(Deserialize, Clone)]
+pub struct RssSource {
+    name: String,
+    feed_url: String,
+    permalink_url: String,
+    explanation_url: Option<String>,
+    explanation_api_url: Option<String>,
+}
+
+/// The metadata of a feed item, cached as json like the metadata of the other sources
+#[derive(Serialize, Deserialize)]
+struct RssItem {
+    number: u16,
+    title: String,
+    image_url: String,
+    alt_text: String,
+    date: String,
+}
+
+impl RssSource {
+    fn number_from_link(&self, link: &str) -> Option<u16> {
+        let (prefix, suffix) = self.permalink_url.split_once(NUMBER_PLACEHOLDER)?;
+        link.trim_end_matches('/')
+            .strip_prefix(prefix)?
+            .strip_suffix(suffix.trim_end_matches('/'))?
+            .parse()
+            .ok()
+    }
+
+    /// Items that aren't comics, like announcements, have no image or a link without a number
+    fn parse_item(&self, item: Node) -> Option<RssItem> {
+        let child_text = |name: &str| {
+            item.children()
+                .find(|child| child.tag_name().name() == name)
+                .and_then(|child| child.text())
+                .map(str::trim)
+        };
+        let link = child_text("link")?;
+        let html = child_text("encoded").or(child_text("description"));
+        let image_url = item
+            .children()
+            .find(|child| match child.tag_name().name() {
+                "enclosure" => child
+                    .attribute("type")
+                    .is_some_and(|kind| kind.starts_with("image/")),
+                "content" | "thumbnail" => child.has_attribute("url"),
+                _ => false,
+            })
+            .and_then(|child| child.attribute("url"))
+            .or_else(|| html_attribute(html?, "img", "src"))?;
+        let date = child_text("pubDate").unwrap_or_default();
+        Some(RssItem {
+            number: self.number_from_link(link)?,
+            title: child_text("title").unwrap_or_default().to_string(),
+            image_url: image_url.to_string(),
+            alt_text: html
+                .and_then(|html| html_attribute(html, "img", "title"))
+                .unwrap_or_default()
+                .to_string(),
+            date: DateTime::parse_from_rfc2822(date).map_or_else(
+                |_| date.to_string(),
+                |date| date.format("%Y-%m-%d").to_string(),
+            ),
+        })
+    }
+}
+
+impl ComicSource for RssSource {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn latest_metadata_url(&self) -> String {
+        self.feed_url.clone()
+    }
+
+    fn metadata_url(&self, _number: u16) -> String {
+        self.feed_url.clone()
+    }
+
+    fn split_metadata(&self, text: String) -> Result<Vec<String>, ComicError> {
+        let feed = Document::parse(&text)?;
+        feed.descendants()
+            .filter(|node| node.has_tag_name("item"))
+            .filter_map(|item| self.parse_item(item))
+            .map(|item| Ok(serde_json::to_string(&item)?))
+            .collect()
+    }
+
+    fn parse_metadata(&self, text: &str) -> Result<Comic, ComicError> {
+        let item: RssItem = serde_json::from_str(text)?;
+        Ok(Comic {
+            safe_name: item.title.clone(),
+            name: item.title,
+            number: item.number,
+            alt_text: item.alt_text,
+            transcript: String::new(),
+            date_uploaded: item.date,
+            interactive: false,
+            image_url: item.image_url,
+            link: String::new(),
+            news: String::new(),
+        })
+    }
+
+    fn permalink(&self, number: u16) -> String {
+        self.permalink_url
+            .replace(NUMBER_PLACEHOLDER, &number.to_string())
+    }
+
+    fn explanation_link(&self, number: u16) -> Option<String> {
+        Some(
+            self.explanation_url
+                .as_ref()?
+                .replace(NUMBER_PLACEHOLDER, &number.to_string()),
+        )
+    }
+
+    fn explanation_api_url(&self, number: u16) -> Option<String> {
+        Some(
+            self.explanation_api_url
+                .as_ref()?
+                .replace(NUMBER_PLACEHOLDER, &number.to_string()),
+        )
+    }
+}
+
+/// The value of the attribute of the first such tag in the html, which feeds embed as text
+fn html_attribute<'a>(html: &'a str, tag: &str, attribute: &str) -> Option<&'a str> {
+    let tag_start = html.find(&format!("<{tag} "))?;
+    let tag = &html[tag_start..tag_start + html[tag_start..].find('>')?];
+    let value_start = tag.find(&format!(" {attribute}="))? + attribute.len() + 2;
+    let quote = tag[value_start..]
+        .chars()
+        .next()
+        .filter(|quote| *quote == '"' || *quote == '\'')?;
+    let value = &tag[value_start + 1..];
+    Some(&value[..value.find(quote)?])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FEED: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:media="http://search.yahoo.com/mrss/">
+<channel>
+<title>Comic</title>
+<item>
+<title>Announcement</title>
+<link>https://example.com/news/shop</link>
+<description>&lt;img src="https://example.com/shop.png"&gt;</description>
+</item>
+<item>
+<title>Second</title>
+<link>https://example.com/comic/2/</link>
+<pubDate>Tue, 02 Jan 2024 10:00:00 +0000</pubDate>
+<media:content url="https://example.com/2.png"/>
+</item>
+<item>
+<title>First</title>
+<link>https://example.com/comic/1/</link>
+<pubDate>Mon, 01 Jan 2024 10:00:00 +0000</pubDate>
+<description>&lt;p&gt;&lt;img src="https://example.com/1.png" title='Alt text'&gt;&lt;/p&gt;</description>
+</item>
+</channel>
+</rss>"#;
+
+    #[test]
+    fn feed_items() {
+        let source = RssSource {
+            name: "comic".to_string(),
+            feed_url: "https://example.com/rss".to_string(),
+            permalink_url: "https://example.com/comic/{number}/".to_string(),
+            explanation_url: None,
+            explanation_api_url: None,
+        };
+        let comics: Vec<Comic> = source
+            .split_metadata(FEED.to_string())
+            .unwrap()
+            .iter()
+            .map(|metadata| source.parse_metadata(metadata).unwrap())
+            .collect();
+        let summary: Vec<_> = comics
+            .iter()
+            .map(|comic| {
+                (
+                    comic.number(),
+                    comic.name(),
+                    comic.image_url(),
+                    comic.alt_text(),
+                    comic.date_uploaded(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (2, "Second", "https://example.com/2.png", "", "2024-01-02"),
+                (
+                    1,
+                    "First",
+                    "https://example.com/1.png",
+                    "Alt text",
+                    "2024-01-01"
+                ),
+            ]
+        );
+        assert!(source.split_metadata("<rss>".to_string()).is_err());
+    }
+}
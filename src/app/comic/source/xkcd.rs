@@ -0,0 +1,130 @@
+use serde_json::Value;
+
+use super::{Comic, ComicError, ComicSource};
+
+pub struct XkcdSource {
+    url: String,
+    api_url: String,
+    explanation_url: String,
+    explanation_api_url: String,
+}
+
+impl XkcdSource {
+    /// The comic number and file names are appended to the url and api url, so they are made to end
+    /// with a slash, like `--api-url http://localhost:8000`
+    pub fn new(
+        url: String,
+        api_url: String,
+        explanation_url: String,
+        explanation_api_url: String,
+    ) -> Self {
+        Self {
+            url: with_trailing_slash(url),
+            api_url: with_trailing_slash(api_url),
+            explanation_url,
+            explanation_api_url,
+        }
+    }
+}
+
+impl ComicSource for XkcdSource {
+    fn name(&self) -> &str {
+        "xkcd"
+    }
+
+    fn latest_metadata_url(&self) -> String {
+        format!("{}info.0.json", self.api_url)
+    }
+
+    fn metadata_url(&self, number: u16) -> String {
+        format!("{}{number}/info.0.json", self.api_url)
+    }
+
+    fn parse_metadata(&self, text: &str) -> Result<Comic, ComicError> {
+        let json: Value = serde_json::from_str(text)?;
+        let alt_text = str_field(&json, "alt")?.to_string();
+        let name = str_field(&json, "title")?.to_string();
+        let safe_name = json["safe_title"].as_str().unwrap_or(&name).to_string();
+        let date_uploaded = format!(
+            "{}-{:02}-{:02}",
+            str_field(&json, "year")?,
+            number_field(&json, "month")?,
+            number_field(&json, "day")?,
+        );
+        let image_url = str_field(&json, "img")?.to_string();
+        let number = json["num"]
+            .as_u64()
+            .and_then(|number| number.try_into().ok())
+            .ok_or_else(|| ComicError::MissingField("num".to_string()))?;
+        Ok(Comic {
+            name,
+            safe_name,
+            number,
+            alt_text,
+            transcript: json["transcript"].as_str().unwrap_or_default().to_string(),
+            date_uploaded,
+            interactive: !json["extra_parts"].is_null(),
+            image_url,
+            link: json["link"].as_str().unwrap_or_default().to_string(),
+            news: json["news"].as_str().unwrap_or_default().to_string(),
+        })
+    }
+
+    fn permalink(&self, number: u16) -> String {
+        format!("{}{number}", self.url)
+    }
+
+    fn explanation_link(&self, number: u16) -> Option<String> {
+        Some(format!("{}{number}", self.explanation_url))
+    }
+
+    fn explanation_api_url(&self, number: u16) -> Option<String> {
+        Some(format!("{}{number}", self.explanation_api_url))
+    }
+}
+
+fn with_trailing_slash(mut url: String) -> String {
+    if !url.ends_with('/') {
+        url.push('/');
+    }
+    url
+}
+
+fn str_field<'a>(json: &'a Value, field: &str) -> Result<&'a str, ComicError> {
+    json[field]
+        .as_str()
+        .ok_or_else(|| ComicError::MissingField(field.to_string()))
+}
+
+/// Numbers that are stored as strings, like the date
+fn number_field(json: &Value, field: &str) -> Result<u16, ComicError> {
+    str_field(json, field)?
+        .parse()
+        .map_err(|_| ComicError::MissingField(field.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_without_trailing_slash() {
+        for api_url in ["http://localhost:8000", "http://localhost:8000/"] {
+            let source = XkcdSource::new(
+                "https://xkcd.com".to_string(),
+                api_url.to_string(),
+                String::new(),
+                String::new(),
+            );
+            assert_eq!(
+                source.latest_metadata_url(),
+                "http://localhost:8000/info.0.json"
+            );
+            assert_eq!(
+                source.metadata_url(5),
+                "http://localhost:8000/5/info.0.json"
+            );
+            assert_eq!(source.permalink(5), "https://xkcd.com/5");
+        }
+    }
+}
@@ -8,10 +8,28 @@ mod terminal;
 pub(super) use terminal::TerminalConfig;
 use terminal::TerminalConfigRaw;
 
+mod prefetch;
+pub(super) use prefetch::PrefetchConfig;
+
+mod network;
+pub(super) use network::NetworkConfig;
+
+mod image;
+pub(super) use image::ImageConfig;
+use image::ImageConfigRaw;
+
 mod keybindings;
-use super::{Keybindings, SwitchToComic};
+use super::{
+    Keybindings, SwitchToComic,
+    comic::source::{ComicSource, JsonSource, RssSource},
+    ui::ColorMode,
+};
 use bitflags::Flags;
-use color_eyre::{Result, eyre::eyre, owo_colors::OwoColorize};
+use color_eyre::{
+    Result,
+    eyre::{OptionExt, eyre},
+    owo_colors::OwoColorize,
+};
 use figment::{
     Figment,
     providers::{Data, Toml},
@@ -31,11 +49,17 @@ pub fn write_default_config(path: &PathBuf) -> Result<()> {
 }
 
 pub(super) struct Config {
-    pub keep_colors: bool,
+    pub offline: bool,
     pub initial_comic: SwitchToComic,
     pub url: String,
+    pub api_url: String,
     pub explanation_url: String,
+    pub explanation_api_url: String,
+    pub source: SourceConfig,
 
+    pub prefetch: PrefetchConfig,
+    pub network: NetworkConfig,
+    pub image: ImageConfig,
     pub keybindings: Keybindings,
     pub styling: StylingConfig,
     pub terminal: TerminalConfig,
@@ -48,11 +72,32 @@ impl Config {
             .merge(Data::<Toml>::file(path))
             .extract()?;
         Ok(Self {
-            keep_colors: raw.keep_colors,
+            offline: raw.offline,
             url: raw.url,
+            api_url: raw.api_url,
             initial_comic: SwitchToComic::from_str(&raw.initial_comic, false)
                 .map_err(|e| eyre!("Failed to parse initial_comic config option: {e}"))?,
             explanation_url: raw.explanation_url,
+            explanation_api_url: raw.explanation_api_url,
+            source: match raw.source.as_str() {
+                "xkcd" => SourceConfig::Xkcd,
+                "json" => SourceConfig::Json(Box::new(
+                    raw.json_source
+                        .ok_or_eyre("The json source requires the json_source config section")?,
+                )),
+                "rss" => SourceConfig::Rss(Box::new(
+                    raw.rss_source
+                        .ok_or_eyre("The rss source requires the rss_source config section")?,
+                )),
+                source => return Err(eyre!("Unknown comic source {source}")),
+            }
+            .validated()?,
+            prefetch: raw.prefetch,
+            network: raw.network,
+            image: ImageConfig::from_raw(
+                raw.image,
+                deprecated_color_mode(raw.keep_colors, raw.color_mode)?,
+            )?,
             keybindings: parse_keybindings(raw.keybindings)?,
             styling: StylingConfig::from_raw(raw.styling)?,
             terminal: TerminalConfig::from_raw(raw.terminal)?,
@@ -60,18 +105,73 @@ impl Config {
     }
 }
 
+pub(super) enum SourceConfig {
+    Xkcd,
+    Json(Box<JsonSource>),
+    Rss(Box<RssSource>),
+}
+
+impl SourceConfig {
+    /// The name of a configured source separates its cache and state, so it has to be a single
+    /// path component that isn't taken by xkcd
+    fn validated(self) -> Result<Self> {
+        let name = match &self {
+            Self::Xkcd => return Ok(self),
+            Self::Json(source) => source.name(),
+            Self::Rss(source) => source.name(),
+        };
+        if name.is_empty()
+            || name == "xkcd"
+            || name == "."
+            || name == ".."
+            || name.contains(['/', '\\'])
+        {
+            return Err(eyre!(
+                "Invalid comic source name {name:?}, it can't be empty, xkcd or contain path separators"
+            ));
+        }
+        Ok(self)
+    }
+}
+
 #[derive(Deserialize)]
 struct ConfigRaw {
-    keep_colors: bool,
+    /// Deprecated, replaced by the color_mode option of the image section
+    keep_colors: Option<bool>,
+    /// Deprecated, moved into the image section
+    color_mode: Option<ColorMode>,
+    offline: bool,
     initial_comic: String,
     url: String,
+    api_url: String,
     explanation_url: String,
+    explanation_api_url: String,
+    source: String,
+    json_source: Option<JsonSource>,
+    rss_source: Option<RssSource>,
 
+    prefetch: PrefetchConfig,
+    network: NetworkConfig,
+    image: ImageConfigRaw,
     styling: StylingConfigRaw,
     keybindings: HashMap<String, String>,
     terminal: TerminalConfigRaw,
 }
 
+/// The color mode set by the options from before the image section, with the name of the option
+fn deprecated_color_mode(
+    keep_colors: Option<bool>,
+    color_mode: Option<ColorMode>,
+) -> Result<Option<(&'static str, ColorMode)>> {
+    match (keep_colors.filter(|keep| *keep), color_mode) {
+        (Some(_), Some(_)) => Err(eyre!(
+            "The deprecated keep_colors and color_mode options conflict, set only color_mode in the image section"
+        )),
+        (Some(_), None) => Ok(Some(("keep_colors", ColorMode::Keep))),
+        (None, color_mode) => Ok(color_mode.map(|color_mode| ("color_mode", color_mode))),
+    }
+}
+
 type Modifiers<T, const L: usize> = [(&'static str, T); L];
 fn parse_modifiers<T: Flags, const L: usize>(
     split: Vec<&str>,
@@ -87,3 +187,80 @@ fn parse_modifiers<T: Flags, const L: usize>(
         .filter(|(str, _)| split.contains(str))
         .fold(T::empty(), |acc, (_, modifier)| acc.union(modifier)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(name: &str, toml: &str) -> Result<Config> {
+        let path = std::env::temp_dir().join(format!("oxikcde-test-{name}.toml"));
+        fs::write(&path, toml)?;
+        let config = Config::new(&path);
+        fs::remove_file(path)?;
+        config
+    }
+
+    #[test]
+    fn deprecated_keep_colors() {
+        let color_mode = |name, toml| load(name, toml).map(|config| config.image.color_mode);
+        assert_eq!(color_mode("default", "").unwrap(), ColorMode::Grayscale);
+        assert_eq!(
+            color_mode("keep", "keep_colors = true").unwrap(),
+            ColorMode::Keep
+        );
+        assert_eq!(
+            color_mode("no_keep", "keep_colors = false").unwrap(),
+            ColorMode::Grayscale
+        );
+        assert_eq!(
+            color_mode("image", "[image]\ncolor_mode = \"theme\"").unwrap(),
+            ColorMode::Theme
+        );
+        assert!(
+            color_mode(
+                "both",
+                "keep_colors = true\n[image]\ncolor_mode = \"theme\""
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn top_level_color_mode() {
+        let color_mode = |name, toml| load(name, toml).map(|config| config.image.color_mode);
+        assert_eq!(
+            color_mode("top_level", "color_mode = \"theme\"").unwrap(),
+            ColorMode::Theme
+        );
+        assert!(
+            color_mode(
+                "top_level_and_image",
+                "color_mode = \"theme\"\n[image]\ncolor_mode = \"keep\""
+            )
+            .is_err()
+        );
+        assert!(
+            color_mode(
+                "top_level_and_keep",
+                "color_mode = \"theme\"\nkeep_colors = true"
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn source_names() {
+        let source = |name: &str| {
+            load(
+                &format!("source_{}", name.len()),
+                &format!(
+                    "source = \"json\"\n[json_source]\nname = {name:?}\nlatest_url = \"\"\nmetadata_url = \"\"\npermalink_url = \"\"\nnumber_field = \"\"\ntitle_field = \"\"\nimage_field = \"\""
+                ),
+            )
+        };
+        assert!(source("my_comic").is_ok());
+        for name in ["", "xkcd", "..", "../comic", "a/b", "a\\b"] {
+            assert!(source(name).is_err(), "{name}");
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use color_eyre::eyre::{OptionExt, Result, eyre};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+use crate::app::ui::{ColorMode, FilterKind, Levels};
+
+#[derive(Clone)]
+pub struct ImageConfig {
+    pub color_mode: ColorMode,
+    pub levels: Levels,
+    pub use_alpha: bool,
+    /// Never empty
+    pub presets: Vec<PresetConfig>,
+}
+
+#[derive(Clone)]
+pub struct PresetConfig {
+    pub name: String,
+    pub filters: Vec<FilterKind>,
+}
+
+#[derive(Deserialize)]
+struct PresetConfigRaw {
+    name: String,
+    filters: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ImageConfigRaw {
+    color_mode: Option<ColorMode>,
+    foreground_threshold: u8,
+    background_threshold: u8,
+    gamma: f32,
+    use_alpha: bool,
+    presets: Vec<PresetConfigRaw>,
+}
+
+impl ImageConfig {
+    /// The color mode of a deprecated option outside the section, named by the first element, is
+    /// used if the section has none
+    pub fn from_raw(
+        raw: ImageConfigRaw,
+        deprecated_color_mode: Option<(&str, ColorMode)>,
+    ) -> Result<Self> {
+        let color_mode = match (raw.color_mode, deprecated_color_mode) {
+            (Some(_), Some((option, _))) => {
+                return Err(eyre!(
+                    "The deprecated top level {option} option conflicts with the color_mode option of the image section, remove it"
+                ));
+            }
+            (Some(color_mode), None) => color_mode,
+            (None, deprecated_color_mode) => deprecated_color_mode
+                .map(|(_, color_mode)| color_mode)
+                .unwrap_or_default(),
+        };
+        if raw.foreground_threshold >= raw.background_threshold {
+            return Err(eyre!(
+                "The foreground_threshold {} has to be below the background_threshold {}",
+                raw.foreground_threshold,
+                raw.background_threshold
+            ));
+        }
+        if !(raw.gamma.is_finite() && raw.gamma > 0.0) {
+            return Err(eyre!("The gamma {} has to be above 0", raw.gamma));
+        }
+        if raw.presets.is_empty() {
+            return Err(eyre!("At least one image preset is required"));
+        }
+        let mut names = HashSet::new();
+        let presets = raw
+            .presets
+            .into_iter()
+            .map(|preset| {
+                if !names.insert(preset.name.clone()) {
+                    return Err(eyre!(
+                        "There are multiple image presets named {}",
+                        preset.name
+                    ));
+                }
+                let filters = preset
+                    .filters
+                    .iter()
+                    .map(|filter| {
+                        filter.parse().map_err(|error: String| {
+                            eyre!("{error} in the image preset {}", preset.name)
+                        })
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(PresetConfig {
+                    name: preset.name,
+                    filters,
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            color_mode,
+            levels: Levels {
+                foreground_threshold: raw.foreground_threshold,
+                background_threshold: raw.background_threshold,
+                gamma: raw.gamma,
+            },
+            use_alpha: raw.use_alpha,
+            presets,
+        })
+    }
+
+    /// The index of the preset with the name
+    pub fn find_preset(&self, name: &str) -> Result<usize> {
+        self.presets
+            .iter()
+            .position(|preset| preset.name == name)
+            .ok_or_eyre(format!("There is no image preset named {name}"))
+    }
+}
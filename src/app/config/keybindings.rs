@@ -1,6 +1,6 @@
 use crate::{
     SwitchToComic,
-    app::{CommandToApp, Keybindings, OpenInBrowser},
+    app::{CommandToApp, Fit, Keybindings, OpenInBrowser, Pan, ScrollPane, Zoom},
 };
 use color_eyre::eyre::{Context, ContextCompat, OptionExt};
 use color_eyre::{Result, eyre::eyre};
@@ -52,6 +52,10 @@ impl CommandToApp {
         let parsed = match split.next()? {
             "switch_to_comic" => Self::SwitchToComic(SwitchToComic::from_str(split.next()?).ok()?),
             "open_in_browser" => Self::OpenInBrowser(OpenInBrowser::from_str(split.next()?).ok()?),
+            "scroll_pane" => Self::ScrollPane(ScrollPane::from_str(split.next()?).ok()?),
+            "zoom" => Self::Zoom(Zoom::from_str(split.next()?).ok()?),
+            "pan" => Self::Pan(Pan::from_str(split.next()?).ok()?),
+            "fit" => Self::Fit(Fit::from_str(split.next()?).ok()?),
             command => Self::from_str(command).ok()?,
         };
         Some(parsed)
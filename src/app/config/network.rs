@@ -0,0 +1,16 @@
+use serde::Deserialize;
+use std::num::NonZeroUsize;
+
+#[derive(Deserialize, Clone)]
+pub struct NetworkConfig {
+    /// In seconds
+    pub connect_timeout: u64,
+    /// In seconds
+    pub read_timeout: u64,
+    pub retries: u32,
+    /// In milliseconds
+    pub retry_delay: u64,
+    pub proxy: Option<String>,
+    pub user_agent: Option<String>,
+    pub max_concurrent_requests: NonZeroUsize,
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Clone, Copy)]
+pub struct PrefetchConfig {
+    pub ahead: u16,
+    pub behind: u16,
+    pub random: bool,
+}
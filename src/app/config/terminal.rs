@@ -2,17 +2,19 @@ use color_eyre::eyre::{OptionExt, Result};
 use image::Rgb;
 use serde::Deserialize;
 
-use crate::app::parse_image_rgb;
+use crate::app::{parse_image_rgb, ui::Renderer};
 
 pub struct TerminalConfig {
     pub foreground_color: Option<Rgb<u8>>,
     pub background_color: Option<Rgb<u8>>,
+    pub renderer: Renderer,
 }
 
 #[derive(Deserialize)]
 pub struct TerminalConfigRaw {
     foreground_color: String,
     background_color: String,
+    renderer: Renderer,
 }
 
 impl TerminalConfig {
@@ -26,6 +28,7 @@ impl TerminalConfig {
                 "Failed to determine background color with config option {}",
                 &raw.background_color
             ))?,
+            renderer: raw.renderer,
         })
     }
 }
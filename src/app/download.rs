@@ -0,0 +1,161 @@
+use clap::ArgMatches;
+use color_eyre::{Result, eyre::eyre, owo_colors::OwoColorize};
+use futures::{StreamExt, stream};
+use std::{collections::BTreeSet, num::NonZeroUsize, str::FromStr};
+
+use super::{cache, comic::Downloader, load_config, new_downloader};
+
+/// The comics to download, the end is the latest comic if [`None`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComicRange {
+    start: u16,
+    end: Option<u16>,
+}
+
+impl Default for ComicRange {
+    fn default() -> Self {
+        Self {
+            start: 1,
+            end: None,
+        }
+    }
+}
+
+impl FromStr for ComicRange {
+    type Err = String;
+
+    /// Parses `start..=end`, `start..end` and a single number, either bound can be left out. Fails
+    /// if the range is empty
+    fn from_str(range: &str) -> Result<Self, Self::Err> {
+        let parse = |number: &str| {
+            number
+                .parse::<u16>()
+                .map_err(|_| format!("Invalid comic number {number}"))
+        };
+        let (start, end) = if let Some((start, end)) = range.split_once("..=") {
+            (start, (!end.is_empty()).then(|| parse(end)).transpose()?)
+        } else if let Some((start, end)) = range.split_once("..") {
+            let end = (!end.is_empty()).then(|| parse(end)).transpose()?;
+            (start, end.map(|end| end.saturating_sub(1)))
+        } else {
+            (range, Some(parse(range)?))
+        };
+        let start = if start.is_empty() { 1 } else { parse(start)? };
+        if end.is_some_and(|end| end < start) {
+            return Err(format!("The range {range} contains no comics"));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// Populates the cache, so the comics can be read offline
+pub async fn download(cli: &ArgMatches) -> Result<()> {
+    let downloader = new_downloader(&load_config(cli)?, cli)?;
+    if downloader.offline() {
+        return Err(eyre!("Can't download comics while offline"));
+    }
+    let range = cli
+        .get_one::<ComicRange>("range")
+        .copied()
+        .unwrap_or_default();
+    let images = cli.get_flag("images");
+    let jobs = cli
+        .get_one::<NonZeroUsize>("jobs")
+        .expect("Option has default value")
+        .get();
+
+    let latest = downloader.get_latest_comic_number().await?;
+    let source = downloader.source().name();
+    let cached_json = cache::cached_numbers(source).await?;
+    let cached_images = if images {
+        cache::cached_image_numbers(source).await?
+    } else {
+        BTreeSet::new()
+    };
+    let requested = range.start.max(1)..=range.end.unwrap_or(latest).min(latest);
+    let missing: Vec<u16> = requested
+        .clone()
+        .filter(|number| {
+            !cached_json.contains(number) || (images && !cached_images.contains(number))
+        })
+        .collect();
+    let already_cached = requested.count() - missing.len();
+    if already_cached > 0 {
+        println!("Skipping {already_cached} comics that are already cached");
+    }
+
+    let total = missing.len();
+    let mut downloads = stream::iter(missing)
+        .map(|number| download_comic(&downloader, number, images))
+        .buffer_unordered(jobs);
+    let mut failures = Vec::new();
+    let mut done = 0;
+    while let Some((number, result)) = downloads.next().await {
+        done += 1;
+        if let Err(error) = result {
+            failures.push((number, error));
+        }
+        eprint!("\rDownloaded {done}/{total}");
+    }
+    if total > 0 {
+        eprintln!();
+    }
+
+    if failures.is_empty() {
+        println!("Downloaded {} comics", total.green());
+        return Ok(());
+    }
+    failures.sort_by_key(|(number, _)| *number);
+    for (number, error) in &failures {
+        println!("{}: {error:#}", number.yellow());
+    }
+    Err(eyre!(
+        "Failed to download {} of {total} comics",
+        failures.len()
+    ))
+}
+
+async fn download_comic(downloader: &Downloader, number: u16, images: bool) -> (u16, Result<()>) {
+    let result = async {
+        let comic = downloader.download(number).await?;
+        if images {
+            if comic.image_url().is_empty() {
+                return Err(eyre!(if comic.interactive() {
+                    "The interactive comic has no image"
+                } else {
+                    "The comic has no image"
+                }));
+            }
+            downloader
+                .download_image_bytes(number, comic.image_url().to_string())
+                .await?;
+        }
+        Ok(())
+    }
+    .await;
+    (number, result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn range(start: u16, end: Option<u16>) -> Result<ComicRange, String> {
+        Ok(ComicRange { start, end })
+    }
+
+    #[test]
+    fn parse_ranges() {
+        assert_eq!("1..=3".parse(), range(1, Some(3)));
+        assert_eq!("1..3".parse(), range(1, Some(2)));
+        assert_eq!("..=5".parse(), range(1, Some(5)));
+        assert_eq!("500..".parse(), range(500, None));
+        assert_eq!("..".parse(), range(1, None));
+        assert_eq!("42".parse(), range(42, Some(42)));
+        for invalid in [
+            "..0", "..1", "5..3", "5..=4", "", "abc", "1..x", "1...3", "-1..3", "70000",
+        ] {
+            assert!(invalid.parse::<ComicRange>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use clap::ArgMatches;
+use color_eyre::{Result, eyre::eyre, owo_colors::OwoColorize};
+
+use super::{App, SwitchToComic, load_config, new_downloader, search_index::SearchIndex};
+
+/// Prints the comics best matching the query without starting the tui
+pub async fn find(cli: &ArgMatches) -> Result<()> {
+    let downloader = new_downloader(&load_config(cli)?, cli)?;
+    let mut index = SearchIndex::load(downloader.source().name()).await?;
+    if cli.get_flag("update_index") {
+        index = index.update(downloader).await?;
+    }
+    if index.is_empty() {
+        return Err(eyre!(
+            "The search index is empty, build it with the --update-index flag"
+        ));
+    }
+
+    let query = cli
+        .get_many::<String>("query")
+        .expect("Argument is required")
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" ");
+    let results = index.fuzzy_search(&query);
+    let Some((best_match, _)) = results.first() else {
+        return Err(eyre!("No comics found"));
+    };
+    if cli.get_flag("open") {
+        return App::run(cli, Some(SwitchToComic::Specific(*best_match))).await;
+    }
+
+    let count = *cli
+        .get_one::<usize>("count")
+        .expect("Option has default value");
+    for (number, entry) in results.iter().take(count) {
+        println!("{}: {}", number.yellow(), entry.title);
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::text::{Line, Span};
+
+use super::{App, Mode, SwitchToComic, ui::*};
+
+const HELP: &str = " enter: open | esc: close ";
+
+impl App {
+    /// Lists the history from the most recent comic, starting at the current one
+    pub(super) fn show_history(&mut self) -> RenderOption {
+        let history = self.state.history();
+        if history.is_empty() {
+            return RenderOption::ShowMessage("The history is empty".into());
+        }
+        let styling = self.ui.styling_config();
+        let rows = history
+            .iter()
+            .enumerate()
+            .rev()
+            .map(|(position, number)| {
+                let title = self
+                    .search_index
+                    .get(*number)
+                    .map(|entry| entry.title.as_str())
+                    .or_else(|| {
+                        let bookmark = self.state.bookmarks().get(number)?;
+                        Some(bookmark.title.as_str())
+                    })
+                    .unwrap_or_default();
+                let marker = if position == self.state.history_position() {
+                    "> "
+                } else {
+                    "  "
+                };
+                Line::from(vec![
+                    Span::raw(marker),
+                    Span::styled(format!("{number:>5} {title}"), styling.title_style),
+                ])
+            })
+            .collect();
+        let mut popup = ListPopup::new(" History ".to_string(), HELP, rows);
+        popup.select((history.len() - 1).saturating_sub(self.state.history_position()));
+        self.mode = Mode::History;
+        self.ui.show_popup(popup);
+        RenderOption::None
+    }
+
+    pub(super) fn handle_history_key(&mut self, key_event: KeyEvent) -> Result<()> {
+        let Some(popup) = self.ui.popup_mut() else {
+            return Ok(());
+        };
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => popup.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => popup.select_next(),
+            KeyCode::Enter => {
+                let history = self.state.history();
+                let selected = popup.selected().and_then(|index| {
+                    let position = history.len().checked_sub(index + 1)?;
+                    Some((position, *history.get(position)?))
+                });
+                self.close_popup();
+                if let Some((position, number)) = selected {
+                    self.navigate(SwitchToComic::Specific(number), Some(position));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.close_popup(),
+            _ => return Ok(()),
+        }
+        self.update_ui(RenderOption::None)
+    }
+}
@@ -0,0 +1,111 @@
+use color_eyre::Result;
+use ratatui::text::{Line, Span, Text};
+
+use super::{App, task::Task, ui::*};
+
+/// What the side pane shows, it follows the current comic
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub(super) enum Pane {
+    Transcript,
+    Explanation,
+}
+
+impl App {
+    /// Closes the pane if it already shows the content
+    pub(super) fn toggle_pane(&mut self, pane: Pane) {
+        if self.pane == Some(pane) {
+            self.pane = None;
+            self.ui.close_pane();
+            self.explanation_task = Task::terminated();
+        } else {
+            self.pane = Some(pane);
+            self.refresh_pane();
+        }
+    }
+
+    /// Shows the content for the current comic
+    pub(super) fn refresh_pane(&mut self) {
+        match self.pane {
+            Some(Pane::Transcript) => self
+                .ui
+                .show_pane(TextPane::new(" Transcript ", self.transcript_text())),
+            Some(Pane::Explanation) => {
+                self.ui.show_pane(TextPane::new(
+                    " Explanation ",
+                    Text::styled("Loading...", self.ui.styling_config().messages_style),
+                ));
+                let downloader = self.downloader.clone();
+                let number = self.comic.number();
+                self.explanation_task =
+                    Task::spawn(async move { downloader.download_explanation(number).await });
+                return;
+            }
+            None => {}
+        }
+        self.explanation_task = Task::terminated();
+    }
+
+    /// The task is replaced when the comic changes and aborted when the pane is closed, so the
+    /// explanation always belongs to the current comic
+    pub(super) fn on_explanation(&mut self, explanation: Result<String>) -> Result<()> {
+        let styling_config = self.ui.styling_config();
+        let text = match explanation {
+            Ok(wikitext) => wikitext_to_text(&wikitext, styling_config.title_style),
+            Err(error) => Text::styled(format!("{error:#}"), styling_config.errors_style),
+        };
+        self.ui.show_pane(TextPane::new(" Explanation ", text));
+        self.update_ui(RenderOption::None)
+    }
+
+    pub(super) fn scroll_pane(&mut self, scroll: ScrollPane) {
+        let Some(pane) = self.ui.pane_mut() else {
+            return;
+        };
+        match scroll {
+            ScrollPane::Up => pane.scroll_up(1),
+            ScrollPane::Down => pane.scroll_down(1),
+            ScrollPane::PageUp => pane.scroll_up(pane.page_height()),
+            ScrollPane::PageDown => pane.scroll_down(pane.page_height()),
+        }
+    }
+
+    fn transcript_text(&self) -> Text<'static> {
+        let label_style = self.ui.styling_config().title_style;
+        let mut lines: Vec<Line> = [("Link: ", self.comic.link()), ("News: ", self.comic.news())]
+            .into_iter()
+            .filter(|(_, text)| !text.is_empty())
+            .map(|(label, text)| {
+                Line::from(vec![
+                    Span::styled(label, label_style),
+                    Span::raw(text.to_string()),
+                ])
+            })
+            .collect();
+        if !lines.is_empty() {
+            lines.push(Line::default());
+        }
+        if self.comic.transcript().is_empty() {
+            lines.push(Line::styled(
+                "This comic has no transcript",
+                self.ui.styling_config().messages_style,
+            ));
+        } else {
+            lines.extend(
+                self.comic
+                    .transcript()
+                    .lines()
+                    .map(|line| Line::raw(line.to_string())),
+            );
+        }
+        Text::from(lines)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, strum::EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub(super) enum ScrollPane {
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+}
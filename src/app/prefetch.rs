@@ -0,0 +1,202 @@
+use cli_log::info;
+use color_eyre::{Result, eyre::eyre};
+use futures::{
+    FutureExt,
+    future::{FusedFuture, poll_fn},
+};
+use std::{
+    collections::{HashMap, VecDeque},
+    ops::RangeInclusive,
+    task::Poll,
+};
+
+use super::{
+    SwitchToComic,
+    comic::{Comic, Downloader},
+    config::PrefetchConfig,
+    download_image_protocols,
+    state::State,
+    task::Task,
+    ui::{ImageProcessor, ImageProtocols},
+};
+
+const RECENT_COMICS: usize = 8;
+/// How many bytes of images the recent comics may take, infographics take tens of megabytes each
+const RECENT_COMICS_MEMORY: usize = 256 * 1024 * 1024;
+
+pub struct Prefetched {
+    pub comic: Comic,
+    pub image_protocols: ImageProtocols,
+}
+
+/// Downloads and processes the comics around the current one in the background
+pub struct Prefetcher {
+    config: PrefetchConfig,
+    downloader: Downloader,
+    image_processor: ImageProcessor,
+    prefetched: HashMap<u16, Prefetched>,
+    tasks: HashMap<u16, Task<Prefetched>>,
+    random_task: Task<Prefetched>,
+    next_random: Option<u16>,
+    /// The last visited comics and the memory their images take, which are kept even outside the
+    /// window so revisiting them is instant
+    recent: VecDeque<(u16, usize)>,
+}
+
+impl Prefetcher {
+    pub fn new(
+        config: PrefetchConfig,
+        downloader: Downloader,
+        image_processor: ImageProcessor,
+    ) -> Self {
+        Self {
+            config,
+            downloader,
+            image_processor,
+            prefetched: HashMap::new(),
+            tasks: HashMap::new(),
+            random_task: Task::terminated(),
+            next_random: None,
+            recent: VecDeque::with_capacity(RECENT_COMICS),
+        }
+    }
+
+    /// Starts prefetching the neighbours of the current comic, forgetting the comics that are too far away
+    pub fn prefetch(&mut self, current: u16) {
+        let window = self.window(current);
+        let next_random = self.next_random;
+        let recent = &self.recent;
+        self.prefetched.retain(|number, _| {
+            window.contains(number)
+                || Some(*number) == next_random
+                || recent.iter().any(|(recent, _)| recent == number)
+        });
+        self.tasks.retain(|number, _| window.contains(number));
+        if self.downloader.offline() {
+            return;
+        }
+
+        for number in window.filter(|number| *number != current) {
+            if !self.prefetched.contains_key(&number) && !self.tasks.contains_key(&number) {
+                let task = self.spawn(move |downloader| async move {
+                    if number > current && number > downloader.get_latest_comic_number().await? {
+                        return Err(eyre!("Comic {number} doesn't exist yet"));
+                    }
+                    Ok(number)
+                });
+                self.tasks.insert(number, task);
+            }
+        }
+
+        if self.config.random && self.next_random.is_none() && self.random_task.is_terminated() {
+            self.random_task = self.spawn(|downloader| async move {
+                downloader
+                    .get_comic_number(&State::default(), SwitchToComic::Random)
+                    .await
+            });
+        }
+    }
+
+    /// Stores a visited comic so it doesn't have to be downloaded and processed again when going back to it
+    pub fn insert(&mut self, prefetched: Prefetched) {
+        let number = prefetched.comic.number();
+        self.recent.retain(|(recent, _)| *recent != number);
+        if self.recent.len() == RECENT_COMICS {
+            self.recent.pop_front();
+        }
+        self.recent
+            .push_back((number, prefetched.image_protocols.memory_size()));
+        // The comic just visited is kept even if it's larger than the limit
+        while self.recent.len() > 1
+            && self.recent.iter().map(|(_, size)| size).sum::<usize>() > RECENT_COMICS_MEMORY
+        {
+            self.recent.pop_front();
+        }
+        self.prefetched.insert(number, prefetched);
+    }
+
+    /// Returns the comic that [`SwitchToComic`] would switch to if it has already been prefetched
+    pub fn take(&mut self, state: &State, switch_to_comic: SwitchToComic) -> Option<Prefetched> {
+        let current = state.current_comic;
+        let number = match switch_to_comic {
+            SwitchToComic::Next => current.checked_add(1)?,
+            SwitchToComic::Previous => current.checked_sub(1)?,
+            SwitchToComic::First => 1,
+            SwitchToComic::Random => self.next_random?,
+            SwitchToComic::Bookmarked => state.bookmarked_comic()?,
+            SwitchToComic::NextBookmark => state.next_bookmark()?,
+            SwitchToComic::PreviousBookmark => state.previous_bookmark()?,
+            SwitchToComic::Specific(number) => number,
+            SwitchToComic::Latest
+            | SwitchToComic::LastSeen
+            | SwitchToComic::NextUnread
+            | SwitchToComic::RandomUnread => return None,
+        };
+        if self.next_random == Some(number) {
+            self.next_random = None;
+        }
+        self.prefetched.remove(&number)
+    }
+
+    /// Completes when a prefetching task finishes
+    pub async fn next(&mut self) {
+        let result = poll_fn(|cx| {
+            if !self.random_task.is_terminated()
+                && let Poll::Ready(result) = self.random_task.poll_unpin(cx)
+            {
+                return Poll::Ready((true, result));
+            }
+
+            let finished =
+                self.tasks
+                    .iter_mut()
+                    .find_map(|(number, task)| match task.poll_unpin(cx) {
+                        Poll::Ready(result) => Some((*number, result)),
+                        Poll::Pending => None,
+                    });
+            match finished {
+                Some((number, result)) => {
+                    self.tasks.remove(&number);
+                    Poll::Ready((false, result))
+                }
+                None => Poll::Pending,
+            }
+        })
+        .await;
+
+        match result {
+            (random, Ok(prefetched)) => {
+                if random {
+                    self.next_random = Some(prefetched.comic.number());
+                }
+                self.prefetched
+                    .insert(prefetched.comic.number(), prefetched);
+            }
+            (_, Err(error)) => info!("Failed to prefetch comic: {error}"),
+        }
+    }
+
+    fn window(&self, current: u16) -> RangeInclusive<u16> {
+        current.saturating_sub(self.config.behind).max(1)
+            ..=current.saturating_add(self.config.ahead)
+    }
+
+    /// Spawns a task downloading and processing the comic with the number returned by `get_number`
+    fn spawn<F: Future<Output = Result<u16>> + Send + 'static>(
+        &self,
+        get_number: impl FnOnce(Downloader) -> F,
+    ) -> Task<Prefetched> {
+        let downloader = self.downloader.clone();
+        let image_processor = self.image_processor.clone();
+        let get_number = get_number(downloader.clone());
+        Task::spawn(async move {
+            let comic = downloader.download(get_number.await?).await?;
+            let image_protocols =
+                download_image_protocols(&downloader, image_processor, &comic).await?;
+            Ok(Prefetched {
+                comic,
+                image_protocols,
+            })
+        })
+    }
+}
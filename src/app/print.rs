@@ -0,0 +1,43 @@
+use clap::ArgMatches;
+use color_eyre::Result;
+
+use super::{SwitchToComic, load_config, new_downloader, state::State, ui};
+
+/// Draws a comic into the scrollback and exits, for shell greetings
+pub async fn print(cli: &ArgMatches) -> Result<()> {
+    let config = load_config(cli)?;
+    let downloader = new_downloader(&config, cli)?;
+    let preset = if cli.get_flag("original") {
+        None
+    } else {
+        match cli.get_one::<String>("preset") {
+            Some(name) => Some(config.image.find_preset(name)?),
+            None => Some(0),
+        }
+    };
+
+    let switch = *cli
+        .get_one::<SwitchToComic>("comic")
+        .expect("Option has default value");
+    let number = downloader
+        .get_comic_number(&State::new(downloader.source().name()), switch)
+        .await?;
+    let comic = downloader.download(number).await?;
+    let image = downloader
+        .download_image(number, comic.image_url().to_string())
+        .await?;
+    let max_image_height = match cli.get_one::<u16>("height") {
+        Some(height) => *height,
+        None => crossterm::terminal::size()?.1 * 2 / 3,
+    };
+
+    ui::print_inline(
+        &comic,
+        image,
+        preset,
+        max_image_height,
+        config.styling,
+        config.terminal,
+        config.image,
+    )
+}
@@ -0,0 +1,104 @@
+use color_eyre::Result;
+use crossterm::event::{KeyCode, KeyEvent};
+use futures::future::FusedFuture;
+use ratatui::text::{Line, Span};
+
+use super::{App, Mode, PromptEvent, SwitchToComic, search_index::SearchIndex, task::Task, ui::*};
+
+const HELP: &str = " enter: open | esc: close ";
+
+impl App {
+    pub(super) fn show_search_prompt(&mut self) -> RenderOption {
+        if self.search_index.is_empty() {
+            return RenderOption::ShowError(
+                "The search index is empty, build it with update_search_index".to_string(),
+            );
+        }
+        self.mode = Mode::Search;
+        self.ui.show_prompt(Prompt::new(" Search ", String::new()));
+        RenderOption::None
+    }
+
+    pub(super) fn update_search_index(&mut self) -> RenderOption {
+        if !self.index_task.is_terminated() {
+            return RenderOption::ShowMessage("The search index is already being updated".into());
+        }
+        let (index, downloader) = (self.search_index.clone(), self.downloader.clone());
+        self.index_task = Task::spawn(index.update(downloader));
+        RenderOption::ShowMessage("Updating the search index".into())
+    }
+
+    pub(super) fn on_search_index_updated(
+        &mut self,
+        index_update_result: Result<SearchIndex>,
+    ) -> Result<()> {
+        let render_option = match index_update_result {
+            Ok(index) => {
+                self.search_index = index;
+                RenderOption::ShowMessage("Updated the search index".into())
+            }
+            Err(error) => RenderOption::ShowError(format!("{error:#}")),
+        };
+        self.update_ui(render_option)
+    }
+
+    pub(super) fn handle_search_key(&mut self, key_event: KeyEvent) -> Result<()> {
+        let render_option = match self.edit_prompt(key_event) {
+            PromptEvent::Submit(query) => self.show_search_results(&query),
+            PromptEvent::Cancel => {
+                self.mode = Mode::Normal;
+                RenderOption::None
+            }
+            PromptEvent::Edit => RenderOption::None,
+        };
+        self.update_ui(render_option)
+    }
+
+    pub(super) fn handle_search_results_key(&mut self, key_event: KeyEvent) -> Result<()> {
+        let Some(popup) = self.ui.popup_mut() else {
+            return Ok(());
+        };
+        match key_event.code {
+            KeyCode::Up | KeyCode::Char('k') => popup.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => popup.select_next(),
+            KeyCode::Enter => {
+                let selected = popup
+                    .selected()
+                    .and_then(|index| self.search_results.get(index).copied());
+                self.close_popup();
+                if let Some(number) = selected {
+                    self.switch_to_comic(SwitchToComic::Specific(number));
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('q') => self.close_popup(),
+            _ => return Ok(()),
+        }
+        self.update_ui(RenderOption::None)
+    }
+
+    fn show_search_results(&mut self, query: &str) -> RenderOption {
+        self.mode = Mode::Normal;
+        let styling = self.ui.styling_config();
+        let (numbers, rows): (Vec<u16>, Vec<Line<'static>>) = self
+            .search_index
+            .search(query)
+            .into_iter()
+            .map(|(number, entry)| {
+                let row = Line::from(vec![
+                    Span::styled(format!("{number:>5} {} ", entry.title), styling.title_style),
+                    Span::styled(entry.alt_text.clone(), styling.alt_text_style),
+                ]);
+                (number, row)
+            })
+            .unzip();
+        if numbers.is_empty() {
+            return RenderOption::ShowMessage("No comics found".into());
+        }
+
+        self.search_results = numbers;
+        self.mode = Mode::SearchResults;
+        self.ui
+            .show_popup(ListPopup::new(format!(" Results for {query} "), HELP, rows));
+        RenderOption::None
+    }
+}
@@ -0,0 +1,165 @@
+use color_eyre::Result;
+use futures::{StreamExt, stream};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::{
+    SwitchToComic, cache,
+    comic::{Comic, Downloader},
+    state::State,
+};
+
+const MAX_RESULTS: usize = 50;
+/// How similar words have to be to count as a fuzzy match
+const FUZZY_THRESHOLD: f64 = 0.85;
+const CONCURRENT_DOWNLOADS: usize = 8;
+
+/// The searchable text of every indexed comic, stored next to the cached comics
+#[derive(Serialize, Deserialize, Clone, Default)]
+#[serde(transparent)]
+pub struct SearchIndex(BTreeMap<u16, IndexEntry>);
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct IndexEntry {
+    pub title: String,
+    pub safe_title: String,
+    pub alt_text: String,
+    pub transcript: String,
+}
+
+impl SearchIndex {
+    /// Returns an empty index if it wasn't built yet
+    pub async fn load(source: &str) -> Result<Self> {
+        Ok(match cache::read_index(source).await? {
+            Some(bytes) => serde_json::from_slice(&bytes)?,
+            None => Self::default(),
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, number: u16) -> Option<&IndexEntry> {
+        self.0.get(&number)
+    }
+
+    pub fn insert(&mut self, comic: &Comic) {
+        self.0.insert(
+            comic.number(),
+            IndexEntry {
+                title: comic.name().to_string(),
+                safe_title: comic.safe_name().to_string(),
+                alt_text: comic.alt_text().to_string(),
+                transcript: comic.transcript().to_string(),
+            },
+        );
+    }
+
+    /// Indexes every comic up to the latest one that isn't indexed yet and saves the index.
+    /// Comics that fail to download are skipped
+    pub async fn update(mut self, downloader: Downloader) -> Result<Self> {
+        let latest = downloader
+            .get_comic_number(&State::default(), SwitchToComic::Latest)
+            .await?;
+        let missing: Vec<u16> = (1..=latest)
+            .filter(|number| !self.0.contains_key(number))
+            .collect();
+        let comics: Vec<Result<Comic>> = stream::iter(missing)
+            .map(|number| downloader.download(number))
+            .buffer_unordered(CONCURRENT_DOWNLOADS)
+            .collect()
+            .await;
+        comics
+            .into_iter()
+            .flatten()
+            .for_each(|comic| self.insert(&comic));
+        cache::write_index(downloader.source().name(), &serde_json::to_vec(&self)?).await;
+        Ok(self)
+    }
+
+    /// Comics containing every word of the query, best matches first
+    pub fn search(&self, query: &str) -> Vec<(u16, &IndexEntry)> {
+        let query = query.to_lowercase();
+        let words: Vec<&str> = query.split_whitespace().collect();
+        if words.is_empty() {
+            return Vec::new();
+        }
+        let mut results: Vec<(u32, u16, &IndexEntry)> = self
+            .0
+            .iter()
+            .filter_map(|(number, entry)| Some((entry.score(&query, &words)?, *number, entry)))
+            .collect();
+        results.sort_by(|(score, number, _), (other_score, other_number, _)| {
+            other_score.cmp(score).then(number.cmp(other_number))
+        });
+        results
+            .into_iter()
+            .take(MAX_RESULTS)
+            .map(|(_, number, entry)| (number, entry))
+            .collect()
+    }
+
+    /// Comics ranked by how similar their words are to the words of the query, so typos and
+    /// different word forms still match
+    pub fn fuzzy_search(&self, query: &str) -> Vec<(u16, &IndexEntry)> {
+        let query = query.to_lowercase();
+        let words: Vec<&str> = query.split_whitespace().collect();
+        let mut results: Vec<(f64, u16, &IndexEntry)> = self
+            .0
+            .iter()
+            .map(|(number, entry)| (entry.fuzzy_score(&words), *number, entry))
+            .filter(|(score, ..)| *score > 0.0)
+            .collect();
+        results.sort_by(|(score, number, _), (other_score, other_number, _)| {
+            other_score.total_cmp(score).then(number.cmp(other_number))
+        });
+        results
+            .into_iter()
+            .take(MAX_RESULTS)
+            .map(|(_, number, entry)| (number, entry))
+            .collect()
+    }
+}
+
+impl IndexEntry {
+    /// Returns [`None`] if a word isn't found anywhere. Matches in the title count the most
+    fn score(&self, query: &str, words: &[&str]) -> Option<u32> {
+        let title = format!("{} {}", self.title, self.safe_title).to_lowercase();
+        let alt_text = self.alt_text.to_lowercase();
+        let transcript = self.transcript.to_lowercase();
+        let phrase_score = if title.contains(query) { 20 } else { 0 };
+        words.iter().try_fold(phrase_score, |score, word| {
+            let word_score = [(&title, 8), (&alt_text, 3), (&transcript, 1)]
+                .into_iter()
+                .filter(|(text, _)| text.contains(word))
+                .map(|(_, score)| score)
+                .sum::<u32>();
+            (word_score > 0).then_some(score + word_score)
+        })
+    }
+
+    fn fuzzy_score(&self, words: &[&str]) -> f64 {
+        let title = format!("{} {}", self.title, self.safe_title).to_lowercase();
+        let alt_text = self.alt_text.to_lowercase();
+        let transcript = self.transcript.to_lowercase();
+        words
+            .iter()
+            .map(|word| {
+                [(&title, 3.0), (&alt_text, 1.5), (&transcript, 1.0)]
+                    .into_iter()
+                    .map(|(text, weight)| best_similarity(text, word) * weight)
+                    .sum::<f64>()
+            })
+            .sum()
+    }
+}
+
+/// Similarity of the most similar word in the text, 0 if no word is similar enough
+fn best_similarity(text: &str, word: &str) -> f64 {
+    text.split(|char: char| !char.is_alphanumeric())
+        .filter(|text_word| !text_word.is_empty())
+        .map(|text_word| strsim::jaro_winkler(text_word, word))
+        .filter(|similarity| *similarity >= FUZZY_THRESHOLD)
+        .fold(0.0, f64::max)
+}
@@ -0,0 +1,115 @@
+use clap::ArgMatches;
+use color_eyre::Result;
+use serde::Serialize;
+
+use super::{SwitchToComic, comic::Comic, load_config, new_downloader, state::State};
+
+#[derive(Serialize)]
+struct ComicInfo<'a> {
+    number: u16,
+    title: &'a str,
+    safe_title: &'a str,
+    date: &'a str,
+    alt: &'a str,
+    image_url: &'a str,
+    interactive: bool,
+    /// Where clicking the comic leads, empty for most comics
+    link: &'a str,
+    permalink: String,
+}
+
+/// Prints the metadata of a comic without starting the tui
+pub async fn show(cli: &ArgMatches) -> Result<()> {
+    let downloader = new_downloader(&load_config(cli)?, cli)?;
+    let switch = *cli
+        .get_one::<SwitchToComic>("comic")
+        .expect("Option has default value");
+    let number = downloader
+        .get_comic_number(&State::new(downloader.source().name()), switch)
+        .await?;
+    let comic = downloader.download(number).await?;
+    let info = ComicInfo {
+        number: comic.number(),
+        title: comic.name(),
+        safe_title: comic.safe_name(),
+        date: comic.date_uploaded(),
+        alt: comic.alt_text(),
+        image_url: comic.image_url(),
+        interactive: comic.interactive(),
+        link: comic.link(),
+        permalink: downloader.source().permalink(number),
+    };
+
+    if cli.get_flag("json") {
+        println!("{}", serde_json::to_string_pretty(&info)?);
+    } else if let Some(template) = cli.get_one::<String>("template") {
+        println!("{}", fill_template(template, &info)?);
+    } else {
+        print_plain(&comic, &info);
+    }
+    Ok(())
+}
+
+/// Replaces every `{field}` of the template with the field of the comic info in a single pass, so
+/// braces inside the values are kept. Unknown fields are left as they are
+fn fill_template(template: &str, info: &ComicInfo) -> Result<String> {
+    let serde_json::Value::Object(fields) = serde_json::to_value(info)? else {
+        unreachable!("ComicInfo is a struct");
+    };
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        filled.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let value = rest
+            .find('}')
+            .and_then(|end| Some((end, fields.get(&rest[1..end])?)));
+        match value {
+            Some((end, value)) => {
+                match value {
+                    serde_json::Value::String(value) => filled.push_str(value),
+                    value => filled.push_str(&value.to_string()),
+                }
+                rest = &rest[end + 1..];
+            }
+            None => {
+                filled.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    filled.push_str(rest);
+    Ok(filled)
+}
+
+fn print_plain(comic: &Comic, info: &ComicInfo) {
+    println!("{comic}");
+    println!("{}", info.date);
+    println!("{}", info.alt);
+    println!("{}", info.image_url);
+    println!("{}", info.permalink);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn template() {
+        let info = ComicInfo {
+            number: 1,
+            title: "Barrel",
+            safe_title: "Barrel",
+            date: "2006-01-01",
+            alt: "Not {title}",
+            image_url: "",
+            interactive: false,
+            link: "",
+            permalink: "https://xkcd.com/1".to_string(),
+        };
+        assert_eq!(
+            fill_template("{number}: {title} {{alt}} {unknown} {", &info).unwrap(),
+            "1: Barrel {Not {title}} {unknown} {"
+        );
+    }
+}
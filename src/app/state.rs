@@ -1,69 +1,362 @@
+use chrono::{DateTime, Local};
 use cli_log::error;
 use color_eyre::{Result, eyre::ContextCompat};
 use dirs::{data_dir, state_dir};
 use serde::{Deserialize, Serialize};
-use std::{fs, path::PathBuf};
+use std::{collections::BTreeMap, fs, path::PathBuf};
 use tap::Tap;
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct State {
+    /// The comic source the state belongs to, every source has its own state file
+    #[serde(skip)]
+    source: String,
     pub current_comic: u16,
+    #[serde(default)]
+    bookmarks: BTreeMap<u16, Bookmark>,
+    #[serde(default)]
+    seen: ComicSet,
+    #[serde(default)]
+    history: Vec<u16>,
+    /// Index of the current comic in the history
+    #[serde(default)]
+    history_position: usize,
+    /// Only read to migrate state files from before there could be multiple bookmarks
+    #[serde(default, skip_serializing)]
     bookmarked_comic: Option<u16>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Bookmark {
+    #[serde(default)]
+    pub title: String,
+    pub name: Option<String>,
+    pub created: DateTime<Local>,
+}
+
+impl Bookmark {
+    fn new(title: String) -> Self {
+        Self {
+            title,
+            name: None,
+            created: Local::now(),
+        }
+    }
+}
+
+const MAX_HISTORY_LENGTH: usize = 100;
+
+/// A bitset of comic numbers
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(transparent)]
+pub struct ComicSet(Vec<u64>);
+
+impl ComicSet {
+    /// Returns false if the number was already in the set
+    pub fn insert(&mut self, number: u16) -> bool {
+        let (index, bit) = Self::position(number);
+        if index >= self.0.len() {
+            self.0.resize(index + 1, 0);
+        }
+        let inserted = self.0[index] & bit == 0;
+        self.0[index] |= bit;
+        inserted
+    }
+
+    pub fn contains(&self, number: u16) -> bool {
+        let (index, bit) = Self::position(number);
+        self.0.get(index).is_some_and(|word| word & bit != 0)
+    }
+
+    const fn position(number: u16) -> (usize, u64) {
+        (number as usize / 64, 1 << (number % 64))
+    }
+}
+
 impl Default for State {
     fn default() -> Self {
         Self {
+            source: String::new(),
             current_comic: 1,
+            bookmarks: BTreeMap::new(),
+            seen: ComicSet::default(),
+            history: Vec::new(),
+            history_position: 0,
             bookmarked_comic: None,
         }
     }
 }
 
 impl State {
-    pub fn new() -> Self {
-        Self::try_new()
+    pub fn new(source: &str) -> Self {
+        Self::try_new(source)
             .map_err(|error| {
                 error!(
                     "Failed to read comic downloader data in {}: {error}. Using default values",
-                    get_path_to_state_file()
+                    get_path_to_state_file(source)
                         .unwrap_or_default()
                         .to_string_lossy()
                 )
             })
             .unwrap_or_default()
+            .tap_mut(|state| state.source = source.to_string())
     }
 
     pub fn save(&self) -> Result<()> {
-        let path = get_path_to_state_file()?;
+        let path = get_path_to_state_file(&self.source)?;
         fs::create_dir_all(path.parent().unwrap())?;
         Ok(fs::write(path, serde_json::to_string(self).unwrap())?)
     }
 
-    fn try_new() -> Result<Self> {
-        Ok(serde_json::from_str(&fs::read_to_string(
-            get_path_to_state_file()?,
-        )?)?)
+    fn try_new(source: &str) -> Result<Self> {
+        Self::from_json(&fs::read_to_string(get_path_to_state_file(source)?)?)
+    }
+
+    /// Migrates the single bookmark of older state files
+    fn from_json(json: &str) -> Result<Self> {
+        let mut state: Self = serde_json::from_str(json)?;
+        if let Some(bookmarked_comic) = state.bookmarked_comic.take() {
+            state
+                .bookmarks
+                .entry(bookmarked_comic)
+                .or_insert_with(|| Bookmark::new(String::new()));
+        }
+        Ok(state)
     }
 
     // TODO: return an enum
-    pub fn toggle_bookmark(&mut self) -> bool {
-        self.bookmarked_comic = if self.bookmarked_comic == Some(self.current_comic) {
-            None
+    pub fn toggle_bookmark(&mut self, title: &str) -> bool {
+        if self.remove_bookmark(self.current_comic) {
+            false
         } else {
-            Some(self.current_comic)
-        };
-        self.bookmarked_comic.is_some()
+            self.add_bookmark(title)
+        }
+    }
+
+    /// Returns false if the current comic is already bookmarked
+    pub fn add_bookmark(&mut self, title: &str) -> bool {
+        if self.bookmarks.contains_key(&self.current_comic) {
+            return false;
+        }
+        self.bookmarks
+            .insert(self.current_comic, Bookmark::new(title.to_string()));
+        true
+    }
+
+    /// Returns false if the comic wasn't bookmarked
+    pub fn remove_bookmark(&mut self, comic: u16) -> bool {
+        self.bookmarks.remove(&comic).is_some()
+    }
+
+    pub fn rename_bookmark(&mut self, comic: u16, name: Option<String>) {
+        if let Some(bookmark) = self.bookmarks.get_mut(&comic) {
+            bookmark.name = name;
+        }
+    }
+
+    pub fn bookmarks(&self) -> &BTreeMap<u16, Bookmark> {
+        &self.bookmarks
+    }
+
+    /// Marks the current comic as seen, returns whether it was seen before
+    pub fn mark_seen(&mut self) -> bool {
+        !self.seen.insert(self.current_comic)
+    }
+
+    pub fn is_seen(&self, comic: u16) -> bool {
+        self.seen.contains(comic)
     }
 
+    /// Adds the current comic to the history, forgetting the comics that could be gone forward to
+    pub fn visit(&mut self) {
+        if self.history.get(self.history_position) == Some(&self.current_comic) {
+            return;
+        }
+        self.history.truncate(self.history_position + 1);
+        self.history.push(self.current_comic);
+        if self.history.len() > MAX_HISTORY_LENGTH {
+            self.history.remove(0);
+        }
+        self.history_position = self.history.len() - 1;
+    }
+
+    /// Returns the position in the history and the comic there
+    pub fn history_back(&self) -> Option<(usize, u16)> {
+        let position = self.history_position.checked_sub(1)?;
+        Some((position, *self.history.get(position)?))
+    }
+
+    /// Returns the position in the history and the comic there
+    pub fn history_forward(&self) -> Option<(usize, u16)> {
+        let position = self.history_position + 1;
+        Some((position, *self.history.get(position)?))
+    }
+
+    /// The visited comics, from the oldest
+    pub fn history(&self) -> &[u16] {
+        &self.history
+    }
+
+    /// Index of the current comic in the history
+    pub fn history_position(&self) -> usize {
+        self.history_position
+    }
+
+    /// Moves in the history without changing it, the comic at the position should be the current comic
+    pub fn set_history_position(&mut self, position: usize) {
+        self.history_position = position;
+    }
+
+    /// The most recently bookmarked comic
     pub fn bookmarked_comic(&self) -> Option<u16> {
-        self.bookmarked_comic
+        self.bookmarks
+            .iter()
+            .max_by_key(|(_, bookmark)| bookmark.created)
+            .map(|(number, _)| *number)
+    }
+
+    /// The first bookmark after the current comic, wrapping around to the first bookmark
+    pub fn next_bookmark(&self) -> Option<u16> {
+        self.bookmarks
+            .range(self.current_comic + 1..)
+            .chain(&self.bookmarks)
+            .next()
+            .map(|(number, _)| *number)
+    }
+
+    /// The last bookmark before the current comic, wrapping around to the last bookmark
+    pub fn previous_bookmark(&self) -> Option<u16> {
+        self.bookmarks
+            .range(..self.current_comic)
+            .rev()
+            .chain(self.bookmarks.iter().rev())
+            .next()
+            .map(|(number, _)| *number)
     }
 }
 
-fn get_path_to_state_file() -> Result<PathBuf> {
+pub fn get_path_to_data_dir() -> Result<PathBuf> {
     Ok(state_dir()
         .or(data_dir())
         .wrap_err("Unsupported platform")?
-        .tap_mut(|path| path.extend(["oxikcde", "state.json"])))
+        .tap_mut(|path| path.push("oxikcde")))
+}
+
+/// xkcd keeps the state file from before there were multiple sources
+fn get_path_to_state_file(source: &str) -> Result<PathBuf> {
+    let file_name = match source {
+        "xkcd" => "state.json".to_string(),
+        source => format!("state-{source}.json"),
+    };
+    Ok(get_path_to_data_dir()?.tap_mut(|path| path.push(file_name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn visited(comics: &[u16]) -> State {
+        let mut state = State::default();
+        for comic in comics {
+            state.current_comic = *comic;
+            state.visit();
+        }
+        state
+    }
+
+    /// Goes to the position like the app does, after the comic there has been loaded
+    fn go_to(state: &mut State, target: fn(&State) -> Option<(usize, u16)>) {
+        let (position, comic) = target(state).unwrap();
+        state.current_comic = comic;
+        state.set_history_position(position);
+    }
+
+    fn bookmarked(comics: &[u16]) -> State {
+        let mut state = State::default();
+        for comic in comics {
+            state.current_comic = *comic;
+            state.add_bookmark("");
+        }
+        state
+    }
+
+    #[test]
+    fn bookmarks_wrap_around() {
+        let mut state = bookmarked(&[3, 10, 20]);
+        let neighbours = |state: &mut State, comic| {
+            state.current_comic = comic;
+            (state.previous_bookmark(), state.next_bookmark())
+        };
+        assert_eq!(neighbours(&mut state, 10), (Some(3), Some(20)));
+        assert_eq!(neighbours(&mut state, 15), (Some(10), Some(20)));
+        assert_eq!(neighbours(&mut state, 20), (Some(10), Some(3)));
+        assert_eq!(neighbours(&mut state, 1), (Some(20), Some(3)));
+        assert_eq!(neighbours(&mut state, 30), (Some(20), Some(3)));
+
+        let mut state = bookmarked(&[5]);
+        assert_eq!(neighbours(&mut state, 5), (Some(5), Some(5)));
+        let mut state = bookmarked(&[]);
+        assert_eq!(neighbours(&mut state, 5), (None, None));
+    }
+
+    #[test]
+    fn single_bookmark_migration() {
+        let state = State::from_json(r#"{"current_comic": 7, "bookmarked_comic": 42}"#).unwrap();
+        assert_eq!(state.current_comic, 7);
+        assert_eq!(state.bookmarks().keys().collect::<Vec<_>>(), [&42]);
+        assert_eq!(state.bookmarked_comic(), Some(42));
+        assert!(
+            !serde_json::to_string(&state)
+                .unwrap()
+                .contains("bookmarked_comic")
+        );
+
+        let state = State::from_json(
+            r#"{"current_comic": 7, "bookmarked_comic": 42, "bookmarks": {"42": {"title": "Kept", "name": null, "created": "2024-01-01T00:00:00+00:00"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(state.bookmarks()[&42].title, "Kept");
+    }
+
+    #[test]
+    fn history_back_and_forward() {
+        let mut state = visited(&[1, 5, 5, 9]);
+        assert_eq!(state.history(), [1, 5, 9]);
+        assert_eq!(state.history_forward(), None);
+
+        go_to(&mut state, State::history_back);
+        assert_eq!((state.current_comic, state.history_position()), (5, 1));
+        go_to(&mut state, State::history_back);
+        assert_eq!(state.current_comic, 1);
+        assert_eq!(state.history_back(), None);
+
+        go_to(&mut state, State::history_forward);
+        assert_eq!(state.current_comic, 5);
+        assert_eq!(state.history_forward(), Some((2, 9)));
+    }
+
+    #[test]
+    fn visiting_truncates_the_forward_history() {
+        let mut state = visited(&[1, 2, 3]);
+        go_to(&mut state, State::history_back);
+        go_to(&mut state, State::history_back);
+        state.current_comic = 7;
+        state.visit();
+        assert_eq!(state.history(), [1, 7]);
+        assert_eq!(state.history_position(), 1);
+        assert_eq!(state.history_forward(), None);
+    }
+
+    #[test]
+    fn history_is_bounded() {
+        let comics: Vec<u16> = (1..=MAX_HISTORY_LENGTH as u16 + 5).collect();
+        let state = visited(&comics);
+        assert_eq!(state.history().len(), MAX_HISTORY_LENGTH);
+        assert_eq!(state.history()[0], 6);
+        assert_eq!(state.history_position(), MAX_HISTORY_LENGTH - 1);
+        assert_eq!(
+            state.history_back(),
+            Some((MAX_HISTORY_LENGTH - 2, MAX_HISTORY_LENGTH as u16 + 4))
+        );
+    }
 }
@@ -0,0 +1,60 @@
+use color_eyre::Result;
+use futures::{
+    FutureExt,
+    future::{Fuse, FusedFuture},
+};
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::task::{AbortHandle, JoinHandle};
+
+/// A spawned task that gets aborted when dropped, so replacing it cancels the superseded work
+pub struct Task<T> {
+    join_handle: Fuse<JoinHandle<Result<T>>>,
+    abort_handle: Option<AbortHandle>,
+}
+
+impl<T: Send + 'static> Task<T> {
+    pub fn spawn(future: impl Future<Output = Result<T>> + Send + 'static) -> Self {
+        let join_handle = tokio::spawn(future);
+        Self {
+            abort_handle: Some(join_handle.abort_handle()),
+            join_handle: join_handle.fuse(),
+        }
+    }
+}
+
+impl<T> Task<T> {
+    /// A task that has already finished and will never yield
+    pub fn terminated() -> Self {
+        Self {
+            join_handle: Fuse::terminated(),
+            abort_handle: None,
+        }
+    }
+}
+
+impl<T> Future for Task<T> {
+    type Output = Result<T>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.join_handle
+            .poll_unpin(cx)
+            .map(|result| result.map_err(Into::into).and_then(|result| result))
+    }
+}
+
+impl<T> FusedFuture for Task<T> {
+    fn is_terminated(&self) -> bool {
+        self.join_handle.is_terminated()
+    }
+}
+
+impl<T> Drop for Task<T> {
+    fn drop(&mut self) {
+        if let Some(abort_handle) = &self.abort_handle {
+            abort_handle.abort();
+        }
+    }
+}
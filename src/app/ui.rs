@@ -1,19 +1,39 @@
+mod filter;
 mod image;
+mod pane;
+mod popup;
+mod recolor;
 pub mod terminal;
+mod text_image;
+mod viewport;
+mod wikitext;
 
-use super::{comic::Comic, config::StylingConfig, config::TerminalConfig};
+use super::{
+    comic::Comic,
+    config::{ImageConfig, StylingConfig, TerminalConfig},
+};
 use ::image::DynamicImage;
 use color_eyre::Result;
-use image::*;
+pub use filter::FilterKind;
+pub use image::{ImageProcessor, ImageProtocols};
+pub use pane::TextPane;
+pub use popup::{ListPopup, Prompt};
 use ratatui::{
-    DefaultTerminal, Frame,
-    layout::{Constraint, Direction, Flex, Layout, Rect},
-    style::Styled,
+    DefaultTerminal, Frame, Terminal, TerminalOptions, Viewport,
+    backend::CrosstermBackend,
+    layout::{Constraint, Direction, Flex, Layout, Position, Rect},
+    style::{Style, Styled},
     text::{Line, Span},
     widgets::{Block, Paragraph, Wrap},
 };
-use ratatui_image::{Resize, protocol::StatefulProtocol};
+pub use recolor::{ColorMode, Levels};
+use std::{borrow::Cow, io, mem};
 use terminal::*;
+pub use text_image::Renderer;
+pub use viewport::{Fit, ImageViewport, Pan, Zoom};
+pub use wikitext::wikitext_to_text;
+
+const PANE_WIDTH_PERCENTAGE: u16 = 40;
 
 pub struct Ui {
     terminal: DefaultTerminal,
@@ -21,12 +41,18 @@ pub struct Ui {
     image_processor: ImageProcessor,
     styling_config: StylingConfig,
     message: Option<Span<'static>>,
+    loading: bool,
+    seen: bool,
+    popup: Option<ListPopup>,
+    prompt: Option<Prompt>,
+    pane: Option<TextPane>,
+    viewport: ImageViewport,
 }
 
 pub enum RenderOption {
     ShowError(String),
-    ShowMessage(&'static str),
-    NewImage(DynamicImage),
+    ShowMessage(Cow<'static, str>),
+    NewImage(Box<ImageProtocols>),
     DeleteMessage,
     None,
 }
@@ -35,46 +61,43 @@ impl Ui {
     pub fn new(
         styling_config: StylingConfig,
         terminal_config: TerminalConfig,
-        keep_colors: bool,
+        image_config: ImageConfig,
     ) -> Result<Self> {
         let terminal = initialise_terminal()?;
-        let image_processor = ImageProcessor::new(
-            terminal_config
-                .foreground_color
-                .map(Ok)
-                .unwrap_or_else(|| get_color(FOREGROUND_COLOR))?,
-            terminal_config
-                .background_color
-                .map(Ok)
-                .unwrap_or_else(|| get_color(BACKGROUND_COLOR))?,
-            keep_colors,
-        )?;
+        let image_processor = new_image_processor(terminal_config, image_config)?;
         Ok(Self {
             terminal,
             styling_config,
             image_protocols: None,
             image_processor,
             message: None,
+            loading: false,
+            seen: false,
+            popup: None,
+            prompt: None,
+            pane: None,
+            viewport: ImageViewport::default(),
         })
     }
 
     pub fn update(
         &mut self,
         comic: &Comic,
-        process_image: bool,
+        preset: Option<usize>,
         option: RenderOption,
     ) -> Result<()> {
         let current_message = self.message.take();
         self.message = match option {
             RenderOption::ShowMessage(message) => {
-                Some(message.set_style(self.styling_config.messages_style))
+                Some(Span::styled(message, self.styling_config.messages_style))
             }
             RenderOption::ShowError(error) => {
                 Some(error.set_style(self.styling_config.errors_style))
             }
-            RenderOption::NewImage(image) => {
-                self.image_protocols = Some(self.image_processor.image_protocols(image));
-                None
+            RenderOption::NewImage(image_protocols) => {
+                self.image_protocols = Some(*image_protocols);
+                self.viewport = ImageViewport::default();
+                current_message
             }
             RenderOption::None => current_message,
             RenderOption::DeleteMessage => {
@@ -92,9 +115,22 @@ impl Ui {
                     .set_style(self.styling_config.date_style),
             )
             .title_top(
-                Line::styled(format!("{comic}"), self.styling_config.title_style).centered(),
+                Line::from(vec![
+                    Span::styled(format!("{comic}"), self.styling_config.title_style),
+                    Span::styled(
+                        if self.seen { " (seen)" } else { " (new)" },
+                        self.styling_config.messages_style,
+                    ),
+                ])
+                .centered(),
             );
 
+        let title_block = if self.loading {
+            title_block.title_top("Loading...".set_style(self.styling_config.messages_style))
+        } else {
+            title_block
+        };
+
         let title_block = if let Some(message) = self.message.clone() {
             title_block.title_top(message.into_right_aligned_line())
         } else {
@@ -106,44 +142,215 @@ impl Ui {
             .wrap(Wrap::default())
             .set_style(self.styling_config.alt_text_style);
 
+        let border_style = self.styling_config.title_style;
         self.terminal.draw(|frame| {
             render(
                 title_block,
                 alt_text,
+                // Graphics protocols would draw the image over the popup
                 self.image_protocols
                     .as_mut()
-                    .map(|protocols| protocols.get(process_image)),
+                    .filter(|_| self.popup.is_none())
+                    .map(|protocols| (protocols, preset, &mut self.viewport)),
+                self.pane.as_mut(),
+                border_style,
                 frame,
-            )
+            );
+            if let Some(popup) = &mut self.popup {
+                popup.render(frame, border_style);
+            }
+            if let Some(prompt) = &self.prompt {
+                prompt.render(frame, border_style);
+            }
         })?;
         Ok(())
     }
 
-    pub fn clear_image_protocols(&mut self) {
-        self.image_protocols = None;
+    pub fn set_loading(&mut self, loading: bool) {
+        self.loading = loading;
     }
+
+    /// Whether the comic was seen before being shown now
+    pub fn set_seen(&mut self, seen: bool) {
+        self.seen = seen;
+    }
+
+    /// Returns the protocols of the previously shown image
+    pub fn replace_image_protocols(
+        &mut self,
+        image_protocols: Option<ImageProtocols>,
+    ) -> Option<ImageProtocols> {
+        self.viewport = ImageViewport::default();
+        mem::replace(&mut self.image_protocols, image_protocols)
+    }
+
+    pub fn viewport_mut(&mut self) -> &mut ImageViewport {
+        &mut self.viewport
+    }
+
+    pub fn show_popup(&mut self, popup: ListPopup) {
+        self.popup = Some(popup);
+    }
+
+    pub fn popup_mut(&mut self) -> Option<&mut ListPopup> {
+        self.popup.as_mut()
+    }
+
+    pub fn close_popup(&mut self) {
+        self.popup = None;
+    }
+
+    pub fn show_pane(&mut self, pane: TextPane) {
+        self.pane = Some(pane);
+    }
+
+    pub fn pane_mut(&mut self) -> Option<&mut TextPane> {
+        self.pane.as_mut()
+    }
+
+    pub fn close_pane(&mut self) {
+        self.pane = None;
+    }
+
+    pub fn show_prompt(&mut self, prompt: Prompt) {
+        self.prompt = Some(prompt);
+    }
+
+    pub fn prompt_mut(&mut self) -> Option<&mut Prompt> {
+        self.prompt.as_mut()
+    }
+
+    /// Returns the entered text
+    pub fn close_prompt(&mut self) -> Option<String> {
+        self.prompt.take().map(|prompt| prompt.text)
+    }
+
+    pub fn styling_config(&self) -> &StylingConfig {
+        &self.styling_config
+    }
+
+    pub fn image_processor(&self) -> &ImageProcessor {
+        &self.image_processor
+    }
+}
+
+/// Draws the comic below the cursor without switching to the alternate screen, so it stays in the
+/// scrollback after exiting
+pub fn print_inline(
+    comic: &Comic,
+    image: DynamicImage,
+    preset: Option<usize>,
+    max_image_height: u16,
+    styling_config: StylingConfig,
+    terminal_config: TerminalConfig,
+    image_config: ImageConfig,
+) -> Result<()> {
+    crossterm::terminal::enable_raw_mode()?;
+    let image_processor = new_image_processor(terminal_config, image_config);
+    crossterm::terminal::disable_raw_mode()?;
+    let mut image_protocols = image_processor?.image_protocols(image);
+
+    let (width, _) = crossterm::terminal::size()?;
+    let image_height = image_protocols
+        .get(preset)
+        .size_for(Rect::new(0, 0, width, max_image_height))
+        .height;
+    let title_block = Block::new()
+        .title_top(comic.date_uploaded().set_style(styling_config.date_style))
+        .title_top(
+            Span::styled(format!("{comic}"), styling_config.title_style).into_centered_line(),
+        );
+    let alt_text = Paragraph::new(comic.alt_text())
+        .centered()
+        .wrap(Wrap::default())
+        .set_style(styling_config.alt_text_style);
+    let height = 1 + image_height + alt_text.line_count(width) as u16;
+
+    let mut terminal = Terminal::with_options(
+        CrosstermBackend::new(io::stdout()),
+        TerminalOptions {
+            viewport: Viewport::Inline(height),
+        },
+    )?;
+    terminal.draw(|frame| {
+        render(
+            title_block,
+            alt_text,
+            Some((&mut image_protocols, preset, &mut ImageViewport::default())),
+            None,
+            Style::new(),
+            frame,
+        )
+    })?;
+    let area = terminal.get_frame().area();
+    terminal.set_cursor_position(Position::new(0, area.bottom().saturating_sub(1)))?;
+    terminal.show_cursor()?;
+    println!();
+    Ok(())
+}
+
+/// Queries the terminal for the colors not set in the config, so the terminal has to be in raw mode.
+/// Terminals that can't be queried get the colors of the Linux console unless graphics are required
+fn new_image_processor(
+    terminal_config: TerminalConfig,
+    image_config: ImageConfig,
+) -> Result<ImageProcessor> {
+    let renderer = terminal_config.renderer;
+    let query_color = |code, fallback| {
+        get_color(code).or_else(|error| match renderer {
+            Renderer::Graphics => Err(error),
+            _ => Ok(fallback),
+        })
+    };
+    ImageProcessor::new(
+        terminal_config
+            .foreground_color
+            .map(Ok)
+            .unwrap_or_else(|| query_color(FOREGROUND_COLOR, FALLBACK_FOREGROUND_COLOR))?,
+        terminal_config
+            .background_color
+            .map(Ok)
+            .unwrap_or_else(|| query_color(BACKGROUND_COLOR, FALLBACK_BACKGROUND_COLOR))?,
+        renderer,
+        image_config,
+    )
 }
 
 fn render(
     title_block: Block,
     alt_text: Paragraph,
-    image: Option<&mut StatefulProtocol>,
+    image: Option<(&mut ImageProtocols, Option<usize>, &mut ImageViewport)>,
+    pane: Option<&mut TextPane>,
+    border_style: Style,
     frame: &mut Frame,
 ) {
     let alt_text_height = alt_text.line_count(frame.area().width) as u16;
     let layout = layout(alt_text_height).split(frame.area());
-    frame.render_widget(title_block, layout[0]);
     frame.render_widget(alt_text, layout[2]);
-    if let Some(image) = image {
-        let image_area = image.size_for(&Resize::Scale(None), layout[1]);
-        let centered_image_area = center_area(
-            layout[1],
-            Constraint::Length(image_area.width),
-            Constraint::Length(image_area.height),
-        );
-
-        frame.render_stateful_widget(IMAGE_WIDGET, centered_image_area, image)
+    let image_area = match pane {
+        Some(pane) => {
+            let [image_area, pane_area] = Layout::horizontal([
+                Constraint::Percentage(100 - PANE_WIDTH_PERCENTAGE),
+                Constraint::Percentage(PANE_WIDTH_PERCENTAGE),
+            ])
+            .areas(layout[1]);
+            pane.render(frame, pane_area, border_style);
+            image_area
+        }
+        None => layout[1],
     };
+    let title_block = match image {
+        Some((image_protocols, preset, viewport)) => {
+            let (image, image_area) = image_protocols.get_cropped(preset, viewport, image_area);
+            image.render(frame, image_area);
+            match viewport.indicator() {
+                Some(indicator) => title_block.title_top(indicator),
+                None => title_block,
+            }
+        }
+        None => title_block,
+    };
+    frame.render_widget(title_block, layout[0]);
 }
 
 fn center_area(area: Rect, horizontal: Constraint, vertical: Constraint) -> Rect {
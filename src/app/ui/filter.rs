@@ -0,0 +1,194 @@
+use super::recolor::Recolor;
+use color_quant::NeuQuant;
+use image::{DynamicImage, GenericImageView, Pixel, Rgb};
+use rayon::prelude::*;
+use std::str::FromStr;
+
+/// How much of the image NeuQuant learns the palette from, 1 uses every pixel and 30 the fewest
+const QUANTIZE_SAMPLE_FACTOR: i32 = 10;
+
+/// One step of a preset, filters run in the order they are configured
+pub trait ImageFilter: Send + Sync {
+    fn apply(&self, image: DynamicImage) -> DynamicImage;
+}
+
+/// A filter as written in the config, like `contrast 1.5`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterKind {
+    Invert,
+    /// Above 1 increases the contrast
+    Contrast(f32),
+    /// Above 1 darkens the grays, like the gamma of the image config section
+    Gamma(f32),
+    /// The radius of the blur the image is sharpened against
+    Sharpen(f32),
+    /// Pixels at least this bright become white and the others black
+    Threshold(u8),
+    /// The size of the palette, from 2 to 256 colors
+    Quantize(usize),
+    /// Themes the image to the terminal colors, configured by the image config section
+    Recolor,
+}
+
+impl FromStr for FilterKind {
+    type Err = String;
+
+    fn from_str(filter: &str) -> Result<Self, Self::Err> {
+        let mut split = filter.split_whitespace();
+        let name = split.next().ok_or("Expected a filter")?;
+        let mut argument = || {
+            split
+                .next()
+                .ok_or_else(|| format!("The {name} filter expects an argument"))
+        };
+        let invalid = |argument: &str| format!("Invalid argument {argument} of the {name} filter");
+        let positive = |argument: &str| {
+            argument
+                .parse::<f32>()
+                .ok()
+                .filter(|value| value.is_finite() && *value > 0.0)
+                .ok_or_else(|| invalid(argument))
+        };
+        let parsed = match name {
+            "invert" => Self::Invert,
+            "contrast" => Self::Contrast(positive(argument()?)?),
+            "gamma" => Self::Gamma(positive(argument()?)?),
+            "sharpen" => Self::Sharpen(positive(argument()?)?),
+            "threshold" => {
+                let argument = argument()?;
+                Self::Threshold(argument.parse().map_err(|_| invalid(argument))?)
+            }
+            "quantize" => {
+                let argument = argument()?;
+                Self::Quantize(
+                    argument
+                        .parse()
+                        .ok()
+                        .filter(|colors| (2..=256).contains(colors))
+                        .ok_or_else(|| invalid(argument))?,
+                )
+            }
+            "recolor" => Self::Recolor,
+            name => return Err(format!("Unknown filter {name}")),
+        };
+        match split.next() {
+            Some(extra) => Err(format!("Unexpected argument {extra} of the {name} filter")),
+            None => Ok(parsed),
+        }
+    }
+}
+
+impl FilterKind {
+    /// The recolor filter is shared, as it is built from the terminal colors
+    pub fn build(self, recolor: &Recolor) -> Box<dyn ImageFilter> {
+        match self {
+            Self::Invert => Box::new(ChannelMap::new(|value| 1.0 - value)),
+            Self::Contrast(factor) => {
+                Box::new(ChannelMap::new(move |value| (value - 0.5) * factor + 0.5))
+            }
+            Self::Gamma(gamma) => Box::new(ChannelMap::new(move |value| value.powf(gamma))),
+            Self::Sharpen(sigma) => Box::new(Sharpen(sigma)),
+            Self::Threshold(level) => Box::new(Threshold(level)),
+            Self::Quantize(colors) => Box::new(Quantize(colors)),
+            Self::Recolor => Box::new(recolor.clone()),
+        }
+    }
+}
+
+/// A named filter chain, which can be cycled through
+pub struct Preset {
+    name: String,
+    filters: Vec<Box<dyn ImageFilter>>,
+}
+
+impl Preset {
+    pub fn new(name: String, filters: Vec<Box<dyn ImageFilter>>) -> Self {
+        Self { name, filters }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn apply(&self, image: &DynamicImage) -> DynamicImage {
+        self.filters
+            .iter()
+            .fold(image.clone(), |image, filter| filter.apply(image))
+    }
+}
+
+impl ImageFilter for Recolor {
+    fn apply(&self, image: DynamicImage) -> DynamicImage {
+        Recolor::apply(self, &image)
+    }
+}
+
+/// Maps every color channel through a curve taking and returning values from 0 to 1, the alpha is
+/// kept
+struct ChannelMap {
+    lookup_table: [u8; 256],
+}
+
+impl ChannelMap {
+    fn new(curve: impl Fn(f32) -> f32) -> Self {
+        Self {
+            lookup_table: std::array::from_fn(|value| {
+                (curve(value as f32 / 255.0).clamp(0.0, 1.0) * 255.0).round() as u8
+            }),
+        }
+    }
+}
+
+impl ImageFilter for ChannelMap {
+    fn apply(&self, image: DynamicImage) -> DynamicImage {
+        map_pixels(image, |pixel| {
+            for channel in &mut pixel[..3] {
+                *channel = self.lookup_table[*channel as usize];
+            }
+        })
+    }
+}
+
+struct Sharpen(f32);
+
+impl ImageFilter for Sharpen {
+    fn apply(&self, image: DynamicImage) -> DynamicImage {
+        image.unsharpen(self.0, 1)
+    }
+}
+
+struct Threshold(u8);
+
+impl ImageFilter for Threshold {
+    fn apply(&self, image: DynamicImage) -> DynamicImage {
+        map_pixels(image, |pixel| {
+            let luma = Rgb([pixel[0], pixel[1], pixel[2]]).to_luma().0[0];
+            let value = if luma >= self.0 { 255 } else { 0 };
+            pixel[..3].fill(value);
+        })
+    }
+}
+
+/// Reduces the image to a palette learned from its own colors
+struct Quantize(usize);
+
+impl ImageFilter for Quantize {
+    fn apply(&self, image: DynamicImage) -> DynamicImage {
+        let image = image.into_rgba8();
+        let quantizer = NeuQuant::new(QUANTIZE_SAMPLE_FACTOR, self.0, image.as_raw());
+        map_pixels(image.into(), |pixel| quantizer.map_pixel(pixel))
+    }
+}
+
+/// Changes the RGBA pixels in place, processing the rows in parallel
+fn map_pixels(image: DynamicImage, map: impl Fn(&mut [u8]) + Sync) -> DynamicImage {
+    let (width, height) = image.dimensions();
+    let mut image = image.into_rgba8();
+    if width == 0 || height == 0 {
+        return image.into();
+    }
+    image
+        .par_chunks_mut(width as usize * 4)
+        .for_each(|row| row.chunks_exact_mut(4).for_each(&map));
+    image.into()
+}
@@ -1,90 +1,223 @@
+use super::{
+    ImageViewport,
+    filter::{ImageFilter, Preset},
+    recolor::Recolor,
+    text_image::{FALLBACK_FONT_SIZE, Renderer, TextImage},
+};
+use crate::app::config::ImageConfig;
 use color_eyre::Result;
-use image::{DynamicImage, GenericImageView, ImageBuffer, Pixel, Rgb};
-use ratatui_image::{Resize, StatefulImage, picker::Picker, protocol::StatefulProtocol};
-use std::array;
+use image::{DynamicImage, GenericImageView, Pixel, Rgb, math::Rect as ImageRect};
+use ratatui::{Frame, layout::Rect};
+use ratatui_image::{
+    Resize, StatefulImage,
+    picker::{Picker, ProtocolType},
+    protocol::StatefulProtocol,
+};
+use std::sync::Arc;
 
-pub const IMAGE_WIDGET: StatefulImage = StatefulImage::new().resize(Resize::Scale(None));
-
-type Color = [u8; 3];
-const WHITE: Color = [255, 255, 255];
-const BLACK: Color = [0, 0, 0];
+const IMAGE_WIDGET: StatefulImage = StatefulImage::new().resize(Resize::Scale(None));
 
+/// The image processed by every preset, a preset of [`None`] is the original image
 pub struct ImageProtocols {
-    original_image_protocol: StatefulProtocol,
-    processed_image_protocol: StatefulProtocol,
+    picker: Picker,
+    original_image_protocol: CroppedProtocol,
+    preset_protocols: Vec<CroppedProtocol>,
 }
 
 impl ImageProtocols {
-    pub fn get(&mut self, process_image: bool) -> &mut StatefulProtocol {
-        if process_image {
-            &mut self.processed_image_protocol
+    pub fn get(&mut self, preset: Option<usize>) -> &mut ImageProtocol {
+        &mut self.cropped_protocol(preset).protocol
+    }
+
+    /// Crops the image to the viewport. Returns the protocol and the part of the area to draw it into
+    pub fn get_cropped(
+        &mut self,
+        preset: Option<usize>,
+        viewport: &mut ImageViewport,
+        area: Rect,
+    ) -> (&mut ImageProtocol, Rect) {
+        let picker = self.picker;
+        let cropped_protocol = self.cropped_protocol(preset);
+        let (crop, image_area) = viewport.crop(
+            cropped_protocol.image.dimensions(),
+            area,
+            picker.font_size(),
+        );
+        if crop != cropped_protocol.crop {
+            cropped_protocol.protocol = cropped_protocol.protocol.with_image(
+                &picker,
+                cropped_protocol
+                    .image
+                    .crop_imm(crop.x, crop.y, crop.width, crop.height),
+            );
+            cropped_protocol.crop = crop;
+        }
+        (&mut cropped_protocol.protocol, image_area)
+    }
+
+    /// Roughly the bytes taken by the images, which are kept both uncropped and in the protocols
+    pub fn memory_size(&self) -> usize {
+        self.preset_protocols
+            .iter()
+            .chain([&self.original_image_protocol])
+            .map(|cropped_protocol| cropped_protocol.image.as_bytes().len() * 2)
+            .sum()
+    }
+
+    fn cropped_protocol(&mut self, preset: Option<usize>) -> &mut CroppedProtocol {
+        match preset.and_then(|preset| self.preset_protocols.get_mut(preset)) {
+            Some(protocol) => protocol,
+            None => &mut self.original_image_protocol,
+        }
+    }
+}
+
+/// The image encoded for the renderer
+pub enum ImageProtocol {
+    Graphics(StatefulProtocol),
+    Text(TextImage),
+}
+
+impl ImageProtocol {
+    fn new(picker: &Picker, renderer: Renderer, image: DynamicImage) -> Self {
+        if renderer.is_text() {
+            Self::Text(TextImage::new(image, renderer))
         } else {
-            &mut self.original_image_protocol
+            Self::Graphics(picker.new_resize_protocol(image))
         }
     }
+
+    /// The same kind of protocol for another image
+    fn with_image(&self, picker: &Picker, image: DynamicImage) -> Self {
+        match self {
+            Self::Graphics(_) => Self::Graphics(picker.new_resize_protocol(image)),
+            Self::Text(text_image) => Self::Text(text_image.with_image(image)),
+        }
+    }
+
+    /// The area the image fills when scaled to fit into the area
+    pub fn size_for(&self, area: Rect) -> Rect {
+        match self {
+            Self::Graphics(protocol) => protocol.size_for(&Resize::Scale(None), area),
+            Self::Text(text_image) => text_image.size_for(area),
+        }
+    }
+
+    pub fn render(&mut self, frame: &mut Frame, area: Rect) {
+        match self {
+            Self::Graphics(protocol) => frame.render_stateful_widget(IMAGE_WIDGET, area, protocol),
+            Self::Text(text_image) => text_image.render(area, frame.buffer_mut()),
+        }
+    }
+}
+
+/// A protocol for part of the image, which is recreated when the part changes
+struct CroppedProtocol {
+    image: DynamicImage,
+    crop: ImageRect,
+    protocol: ImageProtocol,
 }
 
+impl CroppedProtocol {
+    fn new(picker: &Picker, renderer: Renderer, image: DynamicImage) -> Self {
+        let (width, height) = image.dimensions();
+        Self {
+            protocol: ImageProtocol::new(picker, renderer, image.clone()),
+            crop: ImageRect {
+                x: 0,
+                y: 0,
+                width,
+                height,
+            },
+            image,
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct ImageProcessor {
-    foreground_color: Color,
-    background_color: Color,
+    presets: Arc<[Preset]>,
     picker: Picker,
-    keep_colors: bool,
+    /// Never [`Renderer::Auto`]
+    renderer: Renderer,
 }
 
 impl ImageProcessor {
     pub fn new(
         foreground_color: Rgb<u8>,
         background_color: Rgb<u8>,
-        keep_colors: bool,
+        renderer: Renderer,
+        image_config: ImageConfig,
     ) -> Result<Self> {
-        let mut picker = Picker::from_query_stdio()?;
+        let (mut picker, renderer) = match renderer {
+            Renderer::Graphics => (Picker::from_query_stdio()?, Renderer::Graphics),
+            Renderer::Auto => match Picker::from_query_stdio() {
+                Ok(picker) => (picker, Renderer::Graphics),
+                Err(_) => (halfblocks_picker(), Renderer::Halfblocks),
+            },
+            renderer => (halfblocks_picker(), renderer),
+        };
         picker.set_background_color(background_color.to_rgba().0);
+        let recolor = Recolor::new(
+            foreground_color.0,
+            background_color.0,
+            image_config.color_mode,
+            image_config.levels,
+            image_config.use_alpha,
+        );
         Ok(Self {
-            foreground_color: foreground_color.0,
-            background_color: background_color.0,
-            keep_colors,
+            presets: image_config
+                .presets
+                .into_iter()
+                .map(|preset| {
+                    let filters: Vec<Box<dyn ImageFilter>> = preset
+                        .filters
+                        .into_iter()
+                        .map(|filter| filter.build(&recolor))
+                        .collect();
+                    Preset::new(preset.name, filters)
+                })
+                .collect(),
             picker,
+            renderer,
         })
     }
 
+    /// Processes the image with every preset, so switching between them is instant
     pub fn image_protocols(&self, image: DynamicImage) -> ImageProtocols {
         ImageProtocols {
-            processed_image_protocol: self.picker.new_resize_protocol(self.process_image(&image)),
-            original_image_protocol: self.picker.new_resize_protocol(image),
+            picker: self.picker,
+            preset_protocols: self
+                .presets
+                .iter()
+                .map(|preset| {
+                    CroppedProtocol::new(&self.picker, self.renderer, preset.apply(&image))
+                })
+                .collect(),
+            original_image_protocol: CroppedProtocol::new(&self.picker, self.renderer, image),
         }
     }
 
-    fn process_image(&self, image: &DynamicImage) -> DynamicImage {
-        let (width, height) = image.dimensions();
-        let mut out = ImageBuffer::new(width, height);
-
-        image.pixels().for_each(|(x, y, pixel)| {
-            out.put_pixel(x, y, Rgb::from(self.pixel_color(pixel.to_rgb().0)))
-        });
-
-        out.into()
-    }
-
-    fn pixel_color(&self, original_color: Color) -> Color {
-        match original_color {
-            WHITE => self.background_color,
-            BLACK => self.foreground_color,
-            color if is_grayscale(color) || !self.keep_colors => self.blend_color(color),
-            color => color,
+    /// Goes through the presets and then the original image
+    pub fn next_preset(&self, preset: Option<usize>) -> Option<usize> {
+        match preset {
+            Some(preset) if preset + 1 < self.presets.len() => Some(preset + 1),
+            Some(_) => None,
+            None => Some(0),
         }
     }
 
-    fn blend_color(&self, original_color: Color) -> Color {
-        let ratio = Rgb::from(original_color).to_luma().0[0] as f64 / 255.0;
-        array::from_fn(|i| {
-            let fg = self.foreground_color[i] as f64;
-            let bg = self.background_color[i] as f64;
-
-            (bg * ratio + fg * (1.0 - ratio)) as u8
-        })
+    pub fn preset_name(&self, preset: Option<usize>) -> &str {
+        match preset.and_then(|preset| self.presets.get(preset)) {
+            Some(preset) => preset.name(),
+            None => "original",
+        }
     }
 }
 
-const fn is_grayscale([r, g, b]: Color) -> bool {
-    r == g && r == b
+/// Half blocks only need the ratio of the cells, so the terminal doesn't have to be queried
+fn halfblocks_picker() -> Picker {
+    let mut picker = Picker::from_fontsize(FALLBACK_FONT_SIZE);
+    picker.set_protocol_type(ProtocolType::Halfblocks);
+    picker
 }
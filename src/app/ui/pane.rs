@@ -0,0 +1,52 @@
+use ratatui::{
+    Frame,
+    layout::Rect,
+    style::Style,
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Wrap},
+};
+
+/// Scrollable text shown next to the comic
+pub struct TextPane {
+    title: &'static str,
+    text: Text<'static>,
+    scroll: u16,
+    /// The height of the text area when it was last rendered, used for scrolling by pages
+    page_height: u16,
+}
+
+impl TextPane {
+    pub fn new(title: &'static str, text: Text<'static>) -> Self {
+        Self {
+            title,
+            text,
+            scroll: 0,
+            page_height: 0,
+        }
+    }
+
+    pub fn scroll_up(&mut self, lines: u16) {
+        self.scroll = self.scroll.saturating_sub(lines);
+    }
+
+    /// Scrolling past the end is corrected when rendering
+    pub fn scroll_down(&mut self, lines: u16) {
+        self.scroll = self.scroll.saturating_add(lines);
+    }
+
+    pub fn page_height(&self) -> u16 {
+        self.page_height.max(1)
+    }
+
+    pub(super) fn render(&mut self, frame: &mut Frame, area: Rect, border_style: Style) {
+        let block = Block::bordered()
+            .border_style(border_style)
+            .title_top(Line::from(self.title).centered());
+        let inner = block.inner(area);
+        let paragraph = Paragraph::new(self.text.clone()).wrap(Wrap::default());
+        let max_scroll = (paragraph.line_count(inner.width) as u16).saturating_sub(inner.height);
+        self.scroll = self.scroll.min(max_scroll);
+        self.page_height = inner.height;
+        frame.render_widget(paragraph.scroll((self.scroll, 0)).block(block), area);
+    }
+}
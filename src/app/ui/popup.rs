@@ -0,0 +1,106 @@
+use ratatui::{
+    Frame,
+    layout::{Constraint, Position, Rect},
+    style::{Modifier, Style},
+    text::Line,
+    widgets::{Block, Clear, List, ListState, Paragraph},
+};
+
+use super::center_area;
+
+/// A scrollable list drawn over the comic
+pub struct ListPopup {
+    title: String,
+    help: &'static str,
+    rows: Vec<Line<'static>>,
+    state: ListState,
+}
+
+impl ListPopup {
+    pub fn new(title: String, help: &'static str, rows: Vec<Line<'static>>) -> Self {
+        Self {
+            title,
+            help,
+            state: ListState::default().with_selected((!rows.is_empty()).then_some(0)),
+            rows,
+        }
+    }
+
+    pub fn selected(&self) -> Option<usize> {
+        self.state.selected()
+    }
+
+    pub fn select(&mut self, index: usize) {
+        self.state
+            .select(Some(index.min(self.rows.len().saturating_sub(1))));
+    }
+
+    pub fn select_next(&mut self) {
+        self.state.select_next();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.state.select_previous();
+    }
+
+    /// Replaces the rows, keeping the selection in bounds
+    pub fn set_rows(&mut self, rows: Vec<Line<'static>>) {
+        let selected = self
+            .state
+            .selected()
+            .map(|selected| selected.min(rows.len().saturating_sub(1)))
+            .filter(|_| !rows.is_empty());
+        self.state.select(selected);
+        self.rows = rows;
+    }
+
+    pub(super) fn render(&mut self, frame: &mut Frame, border_style: Style) {
+        let area = center_area(
+            frame.area(),
+            Constraint::Percentage(80),
+            Constraint::Percentage(80),
+        );
+        let list = List::new(self.rows.clone())
+            .block(
+                Block::bordered()
+                    .border_style(border_style)
+                    .title_top(Line::from(self.title.as_str()).centered())
+                    .title_bottom(Line::from(self.help).centered()),
+            )
+            .highlight_style(Modifier::REVERSED);
+        frame.render_widget(Clear, area);
+        frame.render_stateful_widget(list, area, &mut self.state);
+    }
+}
+
+/// A single line text input drawn over everything else
+pub struct Prompt {
+    label: &'static str,
+    pub text: String,
+}
+
+impl Prompt {
+    pub fn new(label: &'static str, text: String) -> Self {
+        Self { label, text }
+    }
+
+    pub(super) fn render(&self, frame: &mut Frame, border_style: Style) {
+        let area = center_area(
+            frame.area(),
+            Constraint::Percentage(50),
+            Constraint::Length(3),
+        );
+        let block = Block::bordered()
+            .border_style(border_style)
+            .title_top(Line::from(self.label).centered());
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(self.text.as_str()).block(block), area);
+        frame.set_cursor_position(cursor_position(inner, &self.text));
+    }
+}
+
+fn cursor_position(area: Rect, text: &str) -> Position {
+    let offset = (text.chars().count() as u16).min(area.width.saturating_sub(1));
+    Position::new(area.x + offset, area.y)
+}
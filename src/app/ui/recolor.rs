@@ -0,0 +1,314 @@
+use image::{DynamicImage, GenericImageView, RgbImage};
+use rayon::prelude::*;
+use serde::Deserialize;
+
+pub type Color = [u8; 3];
+
+/// The weights of the red, green and blue channels in the luma, same as the image crate uses
+const LUMA_WEIGHTS: [u32; 3] = [2126, 7152, 722];
+const LUMA_DIVISOR: u32 = 10000;
+/// How often the chroma is halved to find the most saturated color the screen can show
+const GAMUT_STEPS: u32 = 8;
+
+/// How colored pixels are recolored, gray pixels always become a blend of the theme colors
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ColorMode {
+    /// Colors are treated as the gray of their luma
+    #[default]
+    Grayscale,
+    /// Colored pixels are left untouched
+    Keep,
+    /// The lightness is mapped between the theme colors, keeping hue and saturation
+    Theme,
+}
+
+/// Turns the lightness of grays into how far they are from the foreground to the background color
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Levels {
+    /// Grays at most this bright become the foreground color
+    pub foreground_threshold: u8,
+    /// Grays at least this bright become the background color
+    pub background_threshold: u8,
+    /// Above 1 moves the grays in between toward the foreground color
+    pub gamma: f32,
+}
+
+impl Default for Levels {
+    /// Blends linearly, as without levels
+    fn default() -> Self {
+        Self {
+            foreground_threshold: 0,
+            background_threshold: 255,
+            gamma: 1.0,
+        }
+    }
+}
+
+impl Levels {
+    /// Takes and returns values from 0 to 1
+    fn apply(&self, lightness: f32) -> f32 {
+        let foreground = self.foreground_threshold as f32 / 255.0;
+        let background = self.background_threshold as f32 / 255.0;
+        if lightness <= foreground {
+            0.0
+        } else if lightness >= background {
+            1.0
+        } else {
+            ((lightness - foreground) / (background - foreground)).powf(self.gamma)
+        }
+    }
+}
+
+/// Maps the black and white of comics to the terminal's foreground and background colors,
+/// blending the grays in between
+#[derive(Clone)]
+pub struct Recolor {
+    /// The color of every gray, so the blending is only computed once
+    lookup_table: [Color; 256],
+    mode: ColorMode,
+    levels: Levels,
+    /// Whether transparent pixels are drawn over white paper, otherwise their hidden color is used
+    use_alpha: bool,
+    foreground: Oklab,
+    background: Oklab,
+}
+
+impl Recolor {
+    pub fn new(
+        foreground_color: Color,
+        background_color: Color,
+        mode: ColorMode,
+        levels: Levels,
+        use_alpha: bool,
+    ) -> Self {
+        let foreground = Oklab::from_srgb(foreground_color);
+        let background = Oklab::from_srgb(background_color);
+        Self {
+            lookup_table: std::array::from_fn(|gray| match mode {
+                ColorMode::Theme => theme_color(
+                    foreground,
+                    background,
+                    Oklab::from_srgb([gray as u8; 3]),
+                    levels,
+                ),
+                _ => blend_color(
+                    foreground_color,
+                    background_color,
+                    levels.apply(gray as f32 / 255.0),
+                ),
+            }),
+            mode,
+            levels,
+            use_alpha,
+            foreground,
+            background,
+        }
+    }
+
+    /// Processes the rows in parallel, so it should be called off the async runtime
+    pub fn apply(&self, image: &DynamicImage) -> DynamicImage {
+        let (width, height) = image.dimensions();
+        let mut out = RgbImage::new(width, height);
+        if width == 0 || height == 0 {
+            return out.into();
+        }
+
+        let (source, channels) = if self.use_alpha && image.color().has_alpha() {
+            (image.to_rgba8().into_raw(), 4)
+        } else {
+            (image.to_rgb8().into_raw(), 3)
+        };
+        out.par_chunks_mut(width as usize * 3)
+            .zip(source.par_chunks(width as usize * channels))
+            .for_each(|(out_row, row)| {
+                for (out_pixel, pixel) in
+                    out_row.chunks_exact_mut(3).zip(row.chunks_exact(channels))
+                {
+                    let color = match *pixel {
+                        [r, g, b, alpha] => over_paper([r, g, b], alpha),
+                        [r, g, b] => [r, g, b],
+                        _ => unreachable!(),
+                    };
+                    out_pixel.copy_from_slice(&self.pixel_color(color));
+                }
+            });
+        out.into()
+    }
+
+    fn pixel_color(&self, color: Color) -> Color {
+        match self.mode {
+            // The luma of a gray is the gray itself
+            _ if is_grayscale(color) => self.lookup_table[color[0] as usize],
+            ColorMode::Grayscale => self.lookup_table[luma(color) as usize],
+            ColorMode::Keep => color,
+            ColorMode::Theme => theme_color(
+                self.foreground,
+                self.background,
+                Oklab::from_srgb(color),
+                self.levels,
+            ),
+        }
+    }
+}
+
+/// Composites the pixel over white, the color of the paper comics are drawn on
+fn over_paper(color: Color, alpha: u8) -> Color {
+    let alpha = alpha as u32;
+    color.map(|channel| ((channel as u32 * alpha + 255 * (255 - alpha)) / 255) as u8)
+}
+
+/// The ratio goes from 0 for the foreground to 1 for the background
+fn blend_color(foreground_color: Color, background_color: Color, ratio: f32) -> Color {
+    std::array::from_fn(|i| {
+        let fg = foreground_color[i] as f32;
+        let bg = background_color[i] as f32;
+
+        (bg * ratio + fg * (1.0 - ratio)) as u8
+    })
+}
+
+/// Black becomes the foreground and white the background like in [`blend_color`], but in a
+/// perceptual color space so the chroma of the pixel can be added on top. The chroma is reduced
+/// when the color can't be shown, as clipping the channels would shift the hue
+fn theme_color(foreground: Oklab, background: Oklab, color: Oklab, levels: Levels) -> Color {
+    // The thresholds are sRGB grays, so the lightness is turned into the gray that has it
+    let ratio = levels.apply(from_linear(color.l.clamp(0.0, 1.0).powi(3)));
+    let base = Oklab {
+        l: foreground.l + (background.l - foreground.l) * ratio,
+        a: foreground.a + (background.a - foreground.a) * ratio,
+        b: foreground.b + (background.b - foreground.b) * ratio,
+    };
+    let with_chroma = |scale: f32| Oklab {
+        a: base.a + color.a * scale,
+        b: base.b + color.b * scale,
+        ..base
+    };
+
+    if with_chroma(1.0).in_gamut() {
+        return with_chroma(1.0).to_srgb();
+    }
+    let (mut low, mut high) = (0.0, 1.0);
+    for _ in 0..GAMUT_STEPS {
+        let middle = (low + high) / 2.0;
+        if with_chroma(middle).in_gamut() {
+            low = middle;
+        } else {
+            high = middle;
+        }
+    }
+    with_chroma(low).to_srgb()
+}
+
+fn luma(color: Color) -> u8 {
+    let weighted: u32 = color
+        .iter()
+        .zip(LUMA_WEIGHTS)
+        .map(|(channel, weight)| *channel as u32 * weight)
+        .sum();
+    (weighted / LUMA_DIVISOR) as u8
+}
+
+const fn is_grayscale([r, g, b]: Color) -> bool {
+    r == g && r == b
+}
+
+/// A color in the Oklab color space https://bottosson.github.io/posts/oklab/, where lightness
+/// and chroma are perceptually independent
+#[derive(Debug, Clone, Copy)]
+struct Oklab {
+    l: f32,
+    a: f32,
+    b: f32,
+}
+
+impl Oklab {
+    fn from_srgb(color: Color) -> Self {
+        let [r, g, b] = color.map(|channel| to_linear(channel as f32 / 255.0));
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        Self {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+
+    fn to_linear_rgb(self) -> [f32; 3] {
+        let l = (self.l + 0.396_337_78 * self.a + 0.215_803_76 * self.b).powi(3);
+        let m = (self.l - 0.105_561_346 * self.a - 0.063_854_17 * self.b).powi(3);
+        let s = (self.l - 0.089_484_18 * self.a - 1.291_485_5 * self.b).powi(3);
+        [
+            4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+            -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+            -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+        ]
+    }
+
+    fn in_gamut(self) -> bool {
+        self.to_linear_rgb()
+            .iter()
+            .all(|channel| (-0.001..=1.001).contains(channel))
+    }
+
+    fn to_srgb(self) -> Color {
+        self.to_linear_rgb()
+            .map(|channel| (from_linear(channel.clamp(0.0, 1.0)) * 255.0).round() as u8)
+    }
+}
+
+/// Removes the gamma of an sRGB channel
+fn to_linear(channel: f32) -> f32 {
+    if channel <= 0.04045 {
+        channel / 12.92
+    } else {
+        ((channel + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn from_linear(channel: f32) -> f32 {
+    if channel <= 0.003_130_8 {
+        channel * 12.92
+    } else {
+        1.055 * channel.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thresholds_in_every_mode() {
+        const FOREGROUND: Color = [220, 220, 200];
+        const BACKGROUND: Color = [30, 30, 40];
+        let levels = Levels {
+            foreground_threshold: 16,
+            background_threshold: 236,
+            gamma: 1.0,
+        };
+        for mode in [ColorMode::Grayscale, ColorMode::Keep, ColorMode::Theme] {
+            let recolor = Recolor::new(FOREGROUND, BACKGROUND, mode, levels, true);
+            // Theme mode goes through Oklab, so the colors can be off by rounding
+            let assert_close = |gray: u8, expected: Color| {
+                let color = recolor.pixel_color([gray; 3]);
+                assert!(
+                    color
+                        .iter()
+                        .zip(expected)
+                        .all(|(channel, expected)| channel.abs_diff(expected) <= 1),
+                    "gray {gray} became {color:?} instead of {expected:?} in {mode:?}"
+                );
+            };
+            for gray in [0, 8, 16] {
+                assert_close(gray, FOREGROUND);
+            }
+            for gray in [236, 245, 255] {
+                assert_close(gray, BACKGROUND);
+            }
+            let middle = recolor.pixel_color([128; 3]);
+            assert!(middle != FOREGROUND && middle != BACKGROUND, "{mode:?}");
+        }
+    }
+}
@@ -22,6 +22,9 @@ pub fn initialise_terminal() -> Result<DefaultTerminal> {
 
 pub const FOREGROUND_COLOR: u8 = 10;
 pub const BACKGROUND_COLOR: u8 = 11;
+/// The colors of the Linux console, used when the terminal can't be queried
+pub const FALLBACK_FOREGROUND_COLOR: Rgb<u8> = Rgb([170, 170, 170]);
+pub const FALLBACK_BACKGROUND_COLOR: Rgb<u8> = Rgb([0, 0, 0]);
 const DELAY_MS: u64 = 20;
 pub fn get_color(code: u8) -> Result<Rgb<u8>> {
     let string = xterm_query::query_osc(format!("\x1b]{code};?\x1b\\").as_str(), DELAY_MS)
@@ -0,0 +1,206 @@
+use image::{DynamicImage, GenericImageView, Pixel, imageops::FilterType};
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+use ratatui_image::FontSize;
+use serde::Deserialize;
+
+/// The font size assumed when the terminal isn't queried, only the 1:2 ratio of cells matters
+pub const FALLBACK_FONT_SIZE: FontSize = (10, 20);
+/// How much brighter or darker than the paper a pixel has to be to count as ink
+const INK_THRESHOLD: u8 = 64;
+/// Ordered from the least to the most ink
+const ASCII_RAMP: &[u8] = b" .:-=+*#%@";
+/// The bits of the braille dots, by row and column
+const BRAILLE_DOTS: [[u8; 2]; 4] = [[0x01, 0x08], [0x02, 0x10], [0x04, 0x20], [0x40, 0x80]];
+const BRAILLE_BLANK: u32 = 0x2800;
+
+/// How the image is drawn
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Renderer {
+    /// A graphics protocol, falling back to half blocks if the terminal can't be queried
+    #[default]
+    Auto,
+    /// A graphics protocol, failing if the terminal can't be queried
+    Graphics,
+    /// Two pixels per cell with colored half block characters
+    Halfblocks,
+    /// Eight dots per cell with braille characters colored like the ink and paper
+    Braille,
+    /// Plain characters which look denser the more ink there is, for terminals without colors
+    /// or unicode
+    Ascii,
+}
+
+impl Renderer {
+    pub fn is_text(self) -> bool {
+        matches!(self, Self::Braille | Self::Ascii)
+    }
+}
+
+/// An image drawn with characters, encoded again when the area changes
+pub struct TextImage {
+    image: DynamicImage,
+    renderer: Renderer,
+    /// The brightness of the background of the comic, anything far enough from it is ink
+    paper_luma: u8,
+    encoded: Option<(Rect, Vec<TextCell>)>,
+}
+
+struct TextCell {
+    symbol: char,
+    foreground: Option<Color>,
+    background: Option<Color>,
+}
+
+impl TextImage {
+    pub fn new(image: DynamicImage, renderer: Renderer) -> Self {
+        Self {
+            paper_luma: most_common_luma(&image),
+            image,
+            renderer,
+            encoded: None,
+        }
+    }
+
+    /// Keeps the paper of the whole image, so zooming into the ink doesn't invert it
+    pub fn with_image(&self, image: DynamicImage) -> Self {
+        Self {
+            image,
+            renderer: self.renderer,
+            paper_luma: self.paper_luma,
+            encoded: None,
+        }
+    }
+
+    /// The area the image fills when scaled to fit, like [`ratatui_image::Resize::Scale`]
+    pub fn size_for(&self, area: Rect) -> Rect {
+        let (width, height) = self.image.dimensions();
+        let (cell_width, cell_height) = (FALLBACK_FONT_SIZE.0 as f64, FALLBACK_FONT_SIZE.1 as f64);
+        let scale = (area.width as f64 * cell_width / width.max(1) as f64)
+            .min(area.height as f64 * cell_height / height.max(1) as f64);
+        Rect::new(
+            area.x,
+            area.y,
+            ((width as f64 * scale / cell_width).round() as u16).clamp(1, area.width.max(1)),
+            ((height as f64 * scale / cell_height).round() as u16).clamp(1, area.height.max(1)),
+        )
+    }
+
+    pub fn render(&mut self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        let size = Rect::new(0, 0, area.width, area.height);
+        let cells = match &self.encoded {
+            Some((encoded_size, cells)) if *encoded_size == size => cells,
+            _ => {
+                let cells = match self.renderer {
+                    Renderer::Ascii => self.encode_ascii(size),
+                    _ => self.encode_braille(size),
+                };
+                &self.encoded.insert((size, cells)).1
+            }
+        };
+        for (index, text_cell) in cells.iter().enumerate() {
+            let x = area.x + (index % area.width as usize) as u16;
+            let y = area.y + (index / area.width as usize) as u16;
+            if let Some(cell) = buf.cell_mut((x, y)) {
+                cell.set_char(text_cell.symbol);
+                if let Some(foreground) = text_cell.foreground {
+                    cell.set_fg(foreground);
+                }
+                if let Some(background) = text_cell.background {
+                    cell.set_bg(background);
+                }
+            }
+        }
+    }
+
+    fn encode_braille(&self, size: Rect) -> Vec<TextCell> {
+        let image = self
+            .image
+            .resize_exact(
+                size.width as u32 * 2,
+                size.height as u32 * 4,
+                FilterType::Triangle,
+            )
+            .to_rgb8();
+        let mut cells = Vec::with_capacity(size.area() as usize);
+        for row in 0..size.height as u32 {
+            for column in 0..size.width as u32 {
+                let mut bits = 0;
+                let (mut ink, mut paper) = (ColorSum::default(), ColorSum::default());
+                for (dy, dot_row) in BRAILLE_DOTS.iter().enumerate() {
+                    for (dx, bit) in dot_row.iter().enumerate() {
+                        let pixel = image.get_pixel(column * 2 + dx as u32, row * 4 + dy as u32);
+                        if self.is_ink(pixel.to_luma().0[0]) {
+                            bits |= bit;
+                            ink.add(pixel.0);
+                        } else {
+                            paper.add(pixel.0);
+                        }
+                    }
+                }
+                cells.push(TextCell {
+                    symbol: char::from_u32(BRAILLE_BLANK + bits as u32).unwrap_or(' '),
+                    foreground: ink.average(),
+                    background: paper.average(),
+                });
+            }
+        }
+        cells
+    }
+
+    fn encode_ascii(&self, size: Rect) -> Vec<TextCell> {
+        let image = self
+            .image
+            .resize_exact(size.width as u32, size.height as u32, FilterType::Triangle)
+            .to_luma8();
+        image
+            .pixels()
+            .map(|pixel| {
+                let ink = pixel.0[0].abs_diff(self.paper_luma) as usize;
+                TextCell {
+                    symbol: ASCII_RAMP[ink * (ASCII_RAMP.len() - 1) / 255] as char,
+                    foreground: None,
+                    background: None,
+                }
+            })
+            .collect()
+    }
+
+    fn is_ink(&self, luma: u8) -> bool {
+        luma.abs_diff(self.paper_luma) >= INK_THRESHOLD
+    }
+}
+
+#[derive(Default)]
+struct ColorSum {
+    channels: [u32; 3],
+    count: u32,
+}
+
+impl ColorSum {
+    fn add(&mut self, color: [u8; 3]) {
+        for (sum, channel) in self.channels.iter_mut().zip(color) {
+            *sum += channel as u32;
+        }
+        self.count += 1;
+    }
+
+    fn average(&self) -> Option<Color> {
+        let [r, g, b] = self.channels.map(|sum| (sum / self.count.max(1)) as u8);
+        (self.count > 0).then_some(Color::Rgb(r, g, b))
+    }
+}
+
+/// Comics are mostly paper, so the most common brightness is the paper's
+fn most_common_luma(image: &DynamicImage) -> u8 {
+    let mut histogram = [0u32; 256];
+    for pixel in image.to_luma8().pixels() {
+        histogram[pixel.0[0] as usize] += 1;
+    }
+    (0..=255)
+        .max_by_key(|luma| histogram[*luma as usize])
+        .unwrap_or(255)
+}
@@ -0,0 +1,206 @@
+use image::math::Rect as ImageRect;
+use ratatui::layout::{Constraint, Rect};
+use ratatui_image::FontSize;
+use strum::EnumString;
+
+use super::center_area;
+
+const ZOOM_STEP: f64 = 1.25;
+/// How far a pan moves, as a fraction of the visible part of the image
+const PAN_STEP: f64 = 0.25;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Fit {
+    /// The whole image is visible
+    #[default]
+    Whole,
+    Width,
+    Height,
+    /// One image pixel per screen pixel
+    Original,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Zoom {
+    In,
+    Out,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum Pan {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Which part of the image is shown, reset for every comic
+#[derive(Debug, Clone, Copy)]
+pub struct ImageViewport {
+    fit: Fit,
+    /// Zoom steps relative to the fit, clamped when cropping so zooming past the limits has no
+    /// effect to undo
+    zoom_level: i32,
+    /// The center of the view as a fraction of the image size
+    center: (f64, f64),
+    /// The visible fraction of the image when last rendered, used as the pan distance
+    visible: (f64, f64),
+}
+
+impl Default for ImageViewport {
+    fn default() -> Self {
+        Self {
+            fit: Fit::Whole,
+            zoom_level: 0,
+            center: (0.5, 0.5),
+            visible: (1.0, 1.0),
+        }
+    }
+}
+
+impl ImageViewport {
+    /// Starts at the top left, where tall and wide comics begin
+    pub fn set_fit(&mut self, fit: Fit) {
+        *self = Self {
+            fit,
+            center: if fit == Fit::Whole {
+                (0.5, 0.5)
+            } else {
+                (0.0, 0.0)
+            },
+            ..Self::default()
+        };
+    }
+
+    pub fn zoom(&mut self, zoom: Zoom) {
+        self.zoom_level += match zoom {
+            Zoom::In => 1,
+            Zoom::Out => -1,
+        };
+    }
+
+    /// Out of bounds centers are corrected when cropping
+    pub fn pan(&mut self, pan: Pan) {
+        let (x, y) = &mut self.center;
+        match pan {
+            Pan::Left => *x -= self.visible.0 * PAN_STEP,
+            Pan::Right => *x += self.visible.0 * PAN_STEP,
+            Pan::Up => *y -= self.visible.1 * PAN_STEP,
+            Pan::Down => *y += self.visible.1 * PAN_STEP,
+        }
+    }
+
+    /// The part of the image to show and the part of the area to draw it into, so it isn't stretched
+    pub fn crop(
+        &mut self,
+        (width, height): (u32, u32),
+        area: Rect,
+        font_size: FontSize,
+    ) -> (ImageRect, Rect) {
+        let area_width = (area.width as f64 * font_size.0 as f64).max(1.0);
+        let area_height = (area.height as f64 * font_size.1 as f64).max(1.0);
+        let (image_width, image_height) = (width.max(1) as f64, height.max(1) as f64);
+        let fit_scale = match self.fit {
+            Fit::Whole => (area_width / image_width).min(area_height / image_height),
+            Fit::Width => area_width / image_width,
+            Fit::Height => area_height / image_height,
+            Fit::Original => 1.0,
+        };
+        // From the whole image fitting into the area to the crop rounding to a single pixel
+        let whole_scale = (area_width / image_width).min(area_height / image_height);
+        let pixel_scale = area_width.max(area_height) / 1.5;
+        let zoom_levels = |scale: f64| (scale / fit_scale).ln() / ZOOM_STEP.ln();
+        self.zoom_level = self.zoom_level.clamp(
+            (zoom_levels(whole_scale) + f64::EPSILON.sqrt()).floor() as i32,
+            (zoom_levels(pixel_scale) - f64::EPSILON.sqrt()).ceil() as i32,
+        );
+        let scale = fit_scale * ZOOM_STEP.powi(self.zoom_level);
+        let crop_width = (area_width / scale).round().clamp(1.0, image_width);
+        let crop_height = (area_height / scale).round().clamp(1.0, image_height);
+        self.visible = (crop_width / image_width, crop_height / image_height);
+        self.center = (
+            clamp_center(self.center.0, self.visible.0),
+            clamp_center(self.center.1, self.visible.1),
+        );
+        let image_area = center_area(
+            area,
+            Constraint::Length(cells(crop_width * scale, font_size.0, area.width)),
+            Constraint::Length(cells(crop_height * scale, font_size.1, area.height)),
+        );
+
+        let (crop_width, crop_height) = (crop_width as u32, crop_height as u32);
+        let x = ((self.center.0 - self.visible.0 / 2.0) * image_width).round() as u32;
+        let y = ((self.center.1 - self.visible.1 / 2.0) * image_height).round() as u32;
+        let crop = ImageRect {
+            x: x.min(width.saturating_sub(crop_width)),
+            y: y.min(height.saturating_sub(crop_height)),
+            width: crop_width,
+            height: crop_height,
+        };
+        (crop, image_area)
+    }
+
+    /// Describes the visible part, [`None`] if the whole image is visible
+    pub fn indicator(&self) -> Option<String> {
+        if self.visible.0 >= 1.0 && self.visible.1 >= 1.0 {
+            return None;
+        }
+        let range = |center: f64, visible: f64| {
+            let start = ((center - visible / 2.0) * 100.0).round();
+            let end = ((center + visible / 2.0) * 100.0).round();
+            format!("{start}-{end}%")
+        };
+        Some(format!(
+            "x {} y {}",
+            range(self.center.0, self.visible.0),
+            range(self.center.1, self.visible.1)
+        ))
+    }
+}
+
+fn cells(pixels: f64, cell_size: u16, max: u16) -> u16 {
+    ((pixels / cell_size.max(1) as f64).round() as u16).clamp(1, max.max(1))
+}
+
+/// Keeps the visible part inside the image
+fn clamp_center(center: f64, visible: f64) -> f64 {
+    if visible >= 1.0 {
+        0.5
+    } else {
+        center.clamp(visible / 2.0, 1.0 - visible / 2.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONT_SIZE: FontSize = (10, 20);
+    const AREA: Rect = Rect::new(0, 0, 80, 40);
+
+    fn zoomed(viewport: &mut ImageViewport, zoom: Zoom, times: usize) -> ImageRect {
+        for _ in 0..times {
+            viewport.zoom(zoom);
+            viewport.crop((1000, 600), AREA, FONT_SIZE);
+        }
+        viewport.crop((1000, 600), AREA, FONT_SIZE).0
+    }
+
+    #[test]
+    fn zoom_is_clamped() {
+        for fit in [Fit::Whole, Fit::Width, Fit::Height, Fit::Original] {
+            let mut viewport = ImageViewport::default();
+            viewport.set_fit(fit);
+            let whole = zoomed(&mut viewport, Zoom::Out, 100);
+            assert_eq!((whole.width, whole.height), (1000, 600), "{fit:?}");
+            assert_ne!(zoomed(&mut viewport, Zoom::In, 1), whole, "{fit:?}");
+
+            let pixel = zoomed(&mut viewport, Zoom::In, 100);
+            assert_eq!((pixel.width, pixel.height), (1, 1), "{fit:?}");
+            assert_ne!(zoomed(&mut viewport, Zoom::Out, 1), pixel, "{fit:?}");
+        }
+    }
+}
@@ -0,0 +1,340 @@
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span, Text},
+};
+use std::mem;
+
+/// Templates that are replaced with their last argument, like `{{w|Article|label}}`
+const TEXT_TEMPLATES: [&str; 4] = ["w", "wiki", "wikipedia", "xkcd"];
+/// Links to these namespaces are images and metadata, not text
+const HIDDEN_NAMESPACES: [&str; 3] = ["Category:", "File:", "Image:"];
+
+/// Converts MediaWiki markup to styled text. Only the common markup of explanations is handled,
+/// tables are flattened and unknown templates are dropped
+pub fn wikitext_to_text(wikitext: &str, heading_style: Style) -> Text<'static> {
+    let plain = strip_templates(&decode_entities(&strip_tags(wikitext)));
+    let mut lines = Vec::new();
+    for line in plain.lines().map(str::trim_end) {
+        if let Some(heading) = heading(line) {
+            if !lines.is_empty() {
+                lines.push(Line::default());
+            }
+            lines.push(Line::styled(heading.to_string(), heading_style));
+            continue;
+        }
+        let line = match line {
+            "__NOTOC__" | "|-" | "|}" => continue,
+            line if line.starts_with("{|") => continue,
+            line => line,
+        };
+        let (prefix, content) = list_prefix(line);
+        let spans: Vec<Span> = prefix
+            .map(Span::raw)
+            .into_iter()
+            .chain(inline_spans(content))
+            .collect();
+        // Paragraphs are separated by empty lines, so consecutive empty lines are dropped
+        if spans.is_empty() && lines.last().is_none_or(|line: &Line| line.spans.is_empty()) {
+            continue;
+        }
+        lines.push(Line::from(spans));
+    }
+    Text::from(lines)
+}
+
+/// Removes comments and references and turns line breaks into newlines. A `<` only starts a tag
+/// when followed by a letter, `/` or `!`, so inequalities are kept
+fn strip_tags(wikitext: &str) -> String {
+    let mut out = String::with_capacity(wikitext.len());
+    let mut rest = wikitext;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let starts_tag = rest[1..]
+            .chars()
+            .next()
+            .is_some_and(|char| char.is_ascii_alphabetic() || char == '/' || char == '!');
+        let (skip, replacement) = if !starts_tag {
+            (1, "<")
+        } else if rest.starts_with("<!--") {
+            (rest.find("-->").map_or(rest.len(), |end| end + 3), "")
+        } else if rest.starts_with("<ref") {
+            let tag_end = rest.find('>').map_or(rest.len(), |end| end + 1);
+            if rest[..tag_end].ends_with("/>") {
+                (tag_end, "")
+            } else {
+                (rest.find("</ref>").map_or(rest.len(), |end| end + 6), "")
+            }
+        } else if let Some(end) = rest.find('>') {
+            let tag = rest[1..end].trim_start_matches('/').trim();
+            let is_break = tag.split(|char: char| !char.is_alphanumeric()).next() == Some("br");
+            (end + 1, if is_break { "\n" } else { "" })
+        } else {
+            (rest.len(), "")
+        };
+        out.push_str(replacement);
+        rest = &rest[skip..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Decodes the entities explanations commonly use, others are kept as they are
+fn decode_entities(text: &str) -> String {
+    const ENTITIES: [(&str, &str); 5] = [
+        ("&nbsp;", "\u{a0}"),
+        ("&amp;", "&"),
+        ("&lt;", "<"),
+        ("&gt;", ">"),
+        ("&mdash;", "—"),
+    ];
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match ENTITIES.iter().find(|(entity, _)| rest.starts_with(entity)) {
+            Some((entity, decoded)) => {
+                out.push_str(decoded);
+                rest = &rest[entity.len()..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Drops templates, which can be nested and span lines, except the ones that stand for text
+fn strip_templates(wikitext: &str) -> String {
+    let mut out = String::with_capacity(wikitext.len());
+    let mut rest = wikitext;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        // An unclosed template runs to the end of the text
+        let (template, end) = match template_end(rest) {
+            Some(end) => (&rest[2..end - 2], end),
+            None => (&rest[2..], rest.len()),
+        };
+        out.push_str(&template_text(template));
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The byte index after the closing braces of the template at the start of the text, [`None`] if
+/// it isn't closed
+fn template_end(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut index = 0;
+    while index < text.len() {
+        if text[index..].starts_with("{{") {
+            depth += 1;
+            index += 2;
+        } else if text[index..].starts_with("}}") {
+            depth -= 1;
+            index += 2;
+            if depth == 0 {
+                return Some(index);
+            }
+        } else {
+            index += text[index..].chars().next().map_or(1, char::len_utf8);
+        }
+    }
+    None
+}
+
+fn template_text(template: &str) -> String {
+    let arguments: Vec<&str> = template.split('|').map(str::trim).collect();
+    let name = arguments[0].to_lowercase();
+    match name.as_str() {
+        "citation needed" | "cn" => "[citation needed]".to_string(),
+        name if TEXT_TEMPLATES.contains(&name) && arguments.len() > 1 => {
+            arguments[arguments.len() - 1].to_string()
+        }
+        _ => String::new(),
+    }
+}
+
+fn heading(line: &str) -> Option<&str> {
+    let line = line.trim();
+    let level = line.chars().take_while(|char| *char == '=').count();
+    (level >= 2 && line.len() > level * 2 && line.ends_with(&"=".repeat(level)))
+        .then(|| line[level..line.len() - level].trim())
+        .filter(|heading| !heading.is_empty())
+}
+
+/// Turns list and indentation markup into indentation and drops table cell markup
+fn list_prefix(line: &str) -> (Option<String>, &str) {
+    let markers = line
+        .chars()
+        .take_while(|char| matches!(char, '*' | '#' | ':' | ';' | '|' | '!'))
+        .count();
+    if markers == 0 {
+        return (None, line);
+    }
+    let prefix = match line.as_bytes()[markers - 1] {
+        b'*' | b'#' => format!("{}• ", "  ".repeat(markers - 1)),
+        b':' => "  ".repeat(markers),
+        _ => String::new(),
+    };
+    (
+        Some(prefix).filter(|prefix| !prefix.is_empty()),
+        line[markers..].trim_start(),
+    )
+}
+
+/// Applies bold and italic quotes and replaces links with their labels
+fn inline_spans(text: &str) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut current = String::new();
+    let mut modifier = Modifier::empty();
+    let mut rest = text;
+    while let Some(char) = rest.chars().next() {
+        if rest.starts_with("'''") {
+            flush(&mut current, modifier, &mut spans);
+            modifier.toggle(Modifier::BOLD);
+            rest = &rest[3..];
+        } else if rest.starts_with("''") {
+            flush(&mut current, modifier, &mut spans);
+            modifier.toggle(Modifier::ITALIC);
+            rest = &rest[2..];
+        } else if let Some(link) = rest.strip_prefix("[[")
+            && let Some(end) = link.find("]]")
+        {
+            let target = &link[..end];
+            if !HIDDEN_NAMESPACES
+                .iter()
+                .any(|namespace| target.starts_with(namespace))
+            {
+                current.push_str(target.rsplit('|').next().unwrap_or(target));
+            }
+            rest = &link[end + 2..];
+        } else if let Some(link) = rest.strip_prefix('[')
+            && let Some(end) = link.find(']')
+            && link[..end].contains("://")
+        {
+            let link = &link[..end];
+            current.push_str(link.split_once(' ').map_or(link, |(_, label)| label));
+            rest = &rest[end + 2..];
+        } else {
+            current.push(char);
+            rest = &rest[char.len_utf8()..];
+        }
+    }
+    flush(&mut current, modifier, &mut spans);
+    spans
+}
+
+fn flush(current: &mut String, modifier: Modifier, spans: &mut Vec<Span<'static>>) {
+    if !current.is_empty() {
+        spans.push(Span::styled(
+            mem::take(current),
+            Style::new().add_modifier(modifier),
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(wikitext: &str) -> Vec<String> {
+        wikitext_to_text(wikitext, Style::new())
+            .lines
+            .iter()
+            .map(|line| {
+                line.spans
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unclosed_templates() {
+        assert_eq!(plain("{{é}"), Vec::<String>::new());
+        assert_eq!(plain("before {{w|ünclosed"), ["before ünclosed"]);
+        assert_eq!(plain("a {{cn}} b {{"), ["a [citation needed] b"]);
+        assert_eq!(plain("}} {{x|{{y}}"), ["}}"]);
+    }
+
+    #[test]
+    fn non_ascii_text() {
+        assert_eq!(
+            plain("Ça ''marche'' — 日本語 {{w|Zürich}}"),
+            ["Ça marche — 日本語 Zürich"]
+        );
+        let text = wikitext_to_text("'''ß'''", Style::new());
+        assert_eq!(
+            text.lines[0].spans[0].style,
+            Style::new().add_modifier(Modifier::BOLD)
+        );
+    }
+
+    #[test]
+    fn nested_templates() {
+        assert_eq!(
+            plain("a {{outer|{{inner|x}}|y}} b {{w|Article|label}}"),
+            ["a  b label"]
+        );
+        assert_eq!(plain("{{multi\n|line\n}}after"), ["after"]);
+    }
+
+    #[test]
+    fn links() {
+        assert_eq!(
+            plain(
+                "[[Target]] [[Target|label]] [[Category:Comics]][[File:x.png]] \
+                 [https://xkcd.com xkcd] [https://bare.example]"
+            ),
+            ["Target label  xkcd https://bare.example"]
+        );
+        assert_eq!(plain("[[unclosed link"), ["[[unclosed link"]);
+    }
+
+    #[test]
+    fn headings_and_lists() {
+        assert_eq!(
+            plain("==Explanation==\ntext\n\n\n===Trivia===\n* one\n** two\n: indented"),
+            [
+                "Explanation",
+                "text",
+                "",
+                "",
+                "Trivia",
+                "• one",
+                "  • two",
+                "  indented"
+            ]
+        );
+        assert_eq!(plain("== =="), ["== =="]);
+    }
+
+    #[test]
+    fn tags() {
+        assert_eq!(
+            plain("a<!-- comment -->b<ref>note</ref>c<ref name=x/>d<br/>e<span>f</span>"),
+            ["abcd", "ef"]
+        );
+        assert_eq!(plain("unclosed <!-- comment"), ["unclosed"]);
+        assert_eq!(
+            plain("if x < 5 and y > 3 or 1 <= 2"),
+            ["if x < 5 and y > 3 or 1 <= 2"]
+        );
+        assert_eq!(
+            plain("a lone < doesn't\n\neat paragraphs"),
+            ["a lone < doesn't", "", "eat paragraphs"]
+        );
+        assert_eq!(
+            plain("a&nbsp;b &amp; c &lt;br&gt; d&mdash;e &amp;lt; &copy; & f"),
+            ["a\u{a0}b & c <br> d—e &lt; &copy; & f"]
+        );
+    }
+}
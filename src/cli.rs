@@ -1,11 +1,14 @@
-use crate::SwitchToComic;
+use crate::{ComicRange, SwitchToComic};
 use clap::{
-    Arg, ArgAction, ArgMatches,
+    Arg, ArgAction, ArgMatches, Command, ValueEnum,
     builder::{Styles, styling::AnsiColor::*},
-    command, value_parser,
+    command,
+    error::ErrorKind,
+    parser::ValueSource,
+    value_parser,
 };
 use color_eyre::{Result, eyre::ContextCompat};
-use std::{ffi::OsString, path::PathBuf};
+use std::{ffi::OsString, num::NonZeroUsize, path::PathBuf};
 use tap::Tap;
 
 const STYLE: Styles = Styles::styled()
@@ -14,7 +17,37 @@ const STYLE: Styles = Styles::styled()
     .literal(Blue.on_default().bold())
     .placeholder(Cyan.on_default());
 
+/// The arguments of the tui, which can't be combined with subcommands unlike the global arguments
+const TUI_ARGS: [&str; 4] = [
+    "number",
+    "initial_comic",
+    "print_default_config",
+    "write_default_config",
+];
+
 pub fn cli() -> Result<ArgMatches> {
+    Ok(try_cli_from(std::env::args_os()).unwrap_or_else(|error| error.exit()))
+}
+
+fn try_cli_from(
+    args: impl IntoIterator<Item = impl Into<OsString> + Clone>,
+) -> Result<ArgMatches, clap::Error> {
+    let mut command = command().map_err(|error| clap::Error::raw(ErrorKind::Io, error))?;
+    let matches = command.try_get_matches_from_mut(args)?;
+    if let Some(subcommand) = matches.subcommand_name()
+        && let Some(arg) = TUI_ARGS
+            .iter()
+            .find(|arg| matches.value_source(arg) == Some(ValueSource::CommandLine))
+    {
+        return Err(command.error(
+            ErrorKind::ArgumentConflict,
+            format!("the argument '{arg}' cannot be used with the subcommand '{subcommand}'"),
+        ));
+    }
+    Ok(matches)
+}
+
+fn command() -> Result<Command> {
     Ok(command!()
         .args([
             Arg::new("number")
@@ -28,7 +61,19 @@ pub fn cli() -> Result<ArgMatches> {
             Arg::new("config_path")
                 .value_parser(value_parser!(PathBuf))
                 .short('c')
-                .default_value(default_config_path()?),
+                .default_value(default_config_path()?)
+                .global(true),
+            Arg::new("offline")
+                .action(ArgAction::SetTrue)
+                .short('o')
+                .long("offline")
+                .help("Only show cached comics, same as setting the offline config option")
+                .global(true),
+            Arg::new("api_url")
+                .long("api-url")
+                .short('a')
+                .help("Overrides the api_url config option")
+                .global(true),
             Arg::new("print_default_config")
                 .action(ArgAction::SetTrue)
                 .short('p')
@@ -41,8 +86,100 @@ pub fn cli() -> Result<ArgMatches> {
                 .conflicts_with("number")
                 .conflicts_with("initial_comic"),
         ])
-        .styles(STYLE)
-        .get_matches())
+        .subcommand(
+            Command::new("find")
+                .about("Print the comics best matching the query, searching the search index")
+                .args([
+                    Arg::new("query").required(true).num_args(1..),
+                    Arg::new("count")
+                        .value_parser(value_parser!(usize))
+                        .short('n')
+                        .long("count")
+                        .default_value("5")
+                        .help("How many comics to print"),
+                    Arg::new("open")
+                        .action(ArgAction::SetTrue)
+                        .long("open")
+                        .help("Open the best matching comic instead of printing"),
+                    Arg::new("update_index")
+                        .action(ArgAction::SetTrue)
+                        .short('u')
+                        .long("update-index")
+                        .help("Index the comics that aren't indexed yet before searching"),
+                ]),
+        )
+        .subcommand(
+            Command::new("show")
+                .visible_alias("info")
+                .about("Print the metadata of a comic")
+                .args([
+                    Arg::new("comic")
+                        .value_parser(parse_comic)
+                        .default_value("latest")
+                        .help("A comic number or one of latest, first, random, bookmarked, random-unread, last-seen"),
+                    Arg::new("json")
+                        .action(ArgAction::SetTrue)
+                        .short('j')
+                        .long("json")
+                        .help("Print the metadata as json"),
+                    Arg::new("template")
+                        .short('t')
+                        .long("template")
+                        .conflicts_with("json")
+                        .help("Print the template with {number}, {title}, {safe_title}, {date}, {alt}, {image_url}, {interactive}, {link} and {permalink} replaced"),
+                ]),
+        )
+        .subcommand(
+            Command::new("print")
+                .about("Draw a comic into the terminal without starting the tui")
+                .args([
+                    Arg::new("comic")
+                        .value_parser(parse_comic)
+                        .default_value("latest")
+                        .help("A comic number or one of latest, first, random, bookmarked, random-unread, last-seen"),
+                    Arg::new("height")
+                        .value_parser(value_parser!(u16))
+                        .long("height")
+                        .help("The maximum height of the image in rows, two thirds of the terminal by default"),
+                    Arg::new("preset")
+                        .long("preset")
+                        .help("The image preset to process the image with, the first preset by default"),
+                    Arg::new("original")
+                        .action(ArgAction::SetTrue)
+                        .long("original")
+                        .conflicts_with("preset")
+                        .help("Draw the image without processing it"),
+                ]),
+        )
+        .subcommand(
+            Command::new("download")
+                .about("Download comics into the cache for offline use, skipping cached comics")
+                .args([
+                    Arg::new("range")
+                        .value_parser(|range: &str| range.parse::<ComicRange>())
+                        .short('r')
+                        .long("range")
+                        .help("The comics to download like 1..=100, 500.. or 42, all comics by default"),
+                    Arg::new("images")
+                        .action(ArgAction::SetTrue)
+                        .long("images")
+                        .help("Download the images as well"),
+                    Arg::new("jobs")
+                        .value_parser(value_parser!(NonZeroUsize))
+                        .short('j')
+                        .long("jobs")
+                        .default_value("8")
+                        .help("How many comics to download at the same time"),
+                ]),
+        )
+        .styles(STYLE))
+}
+
+fn parse_comic(comic: &str) -> Result<SwitchToComic, String> {
+    comic
+        .parse()
+        .map(SwitchToComic::Specific)
+        .or_else(|_| SwitchToComic::from_str(comic, true))
 }
 
 fn default_config_path() -> Result<OsString> {
@@ -51,3 +188,48 @@ fn default_config_path() -> Result<OsString> {
         .tap_mut(|p| p.extend(["oxikcde", "oxikcde.toml"]))
         .into())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_args_before_and_after_subcommand() {
+        for args in [
+            ["oxikcde", "--offline", "show", "12"],
+            ["oxikcde", "show", "12", "--offline"],
+            ["oxikcde", "-o", "print", "random"],
+            ["oxikcde", "print", "random", "-o"],
+        ] {
+            let matches = try_cli_from(args).unwrap();
+            assert!(matches.get_flag("offline"), "{args:?}");
+            assert!(matches.subcommand().is_some(), "{args:?}");
+        }
+        for args in [
+            ["oxikcde", "-c", "cfg.toml", "find", "foo"],
+            ["oxikcde", "find", "foo", "-c", "cfg.toml"],
+        ] {
+            let matches = try_cli_from(args).unwrap();
+            let (_, find) = matches.subcommand().unwrap();
+            assert_eq!(
+                find.get_one::<PathBuf>("config_path"),
+                Some(&PathBuf::from("cfg.toml")),
+                "{args:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn tui_args_conflict_with_subcommands() {
+        for args in [
+            &["oxikcde", "12", "show"][..],
+            &["oxikcde", "-i", "latest", "find", "foo"],
+            &["oxikcde", "-p", "show"],
+            &["oxikcde", "-w", "download"],
+        ] {
+            let error = try_cli_from(args).unwrap_err();
+            assert_eq!(error.kind(), ErrorKind::ArgumentConflict, "{args:?}");
+        }
+        assert!(try_cli_from(["oxikcde", "12", "--offline"]).is_ok());
+    }
+}
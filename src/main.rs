@@ -29,7 +29,11 @@ async fn main() -> Result<()> {
         );
     }
 
-    let result = App::run(cli).await;
-    ratatui::restore();
-    result
+    match cli.subcommand() {
+        Some(("find", cli)) => app::find(cli).await,
+        Some(("show", cli)) => app::show(cli).await,
+        Some(("print", cli)) => app::print(cli).await,
+        Some(("download", cli)) => app::download(cli).await,
+        _ => App::run(&cli, None).await,
+    }
 }